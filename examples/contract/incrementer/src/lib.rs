@@ -45,5 +45,48 @@ mod incrementer {
             contract.inc_by(42);
             assert_eq!(contract.get(), 84);
         }
+
+        #[test]
+        fn call_works() {
+            let mut contract = Incrementer::new(0);
+            assert_eq!(contract.call::<methods::inc_by>((42,)), Ok(()));
+            assert_eq!(contract.call::<methods::get>(()), Ok(42));
+            assert_eq!(contract.get(), 42);
+        }
+
+        #[test]
+        fn call_raw_works() {
+            let mut contract = Incrementer::new(0);
+            let mut data = <methods::inc_by as liquid::FnSelector>::SELECTOR.to_vec();
+            data.extend(liquid_abi_codec::Encode::encode(&42u128));
+            assert_eq!(contract.call_raw(&data), Ok(vec![]));
+
+            let data = <methods::get as liquid::FnSelector>::SELECTOR.to_vec();
+            assert_eq!(
+                contract.call_raw(&data),
+                Ok(liquid_abi_codec::Encode::encode(&42u128))
+            );
+        }
+
+        #[test]
+        fn call_raw_with_unknown_selector() {
+            let mut contract = Incrementer::new(0);
+            assert_eq!(
+                contract.call_raw(&[0xff; 4]),
+                Err(liquid::DispatchError::UnknownSelector)
+            );
+        }
+
+        #[test]
+        fn call_raw_with_invalid_params() {
+            let mut contract = Incrementer::new(0);
+            let mut data = <methods::inc_by as liquid::FnSelector>::SELECTOR.to_vec();
+            data.extend(&[0x00; 3]);
+            assert_eq!(
+                contract.call_raw(&data),
+                Err(liquid::DispatchError::InvalidParams)
+            );
+            assert_eq!(contract.get(), 0);
+        }
     }
 }
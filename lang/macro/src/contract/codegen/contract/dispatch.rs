@@ -36,11 +36,15 @@ impl<'a> GenerateCode for Dispatch<'a> {
         let entry_point = self.generate_entry_point();
 
         quote! {
-            #[cfg(not(test))]
+            #marker
+
             const _: () = {
-                #marker
                 #traits
                 #dispatch
+            };
+
+            #[cfg(not(test))]
+            const _: () = {
                 #entry_point
             };
         }
@@ -194,9 +194,15 @@ impl<'a> Events<'a> {
             };
 
             let mut impls =  quote_spanned! { span =>
+                impl liquid_primitives::EventSignature for #event_ident {
+                    fn signature() -> liquid_primitives::types::Hash {
+                        #sig_hash.into()
+                    }
+                }
+
                 impl liquid_primitives::Topics for #event_ident {
                     fn topics(&self) -> liquid_prelude::vec::Vec<liquid_primitives::types::Hash> {
-                        [#sig_hash.into(), #topic_hash].to_vec()
+                        [<Self as liquid_primitives::EventSignature>::signature(), #topic_hash].to_vec()
                     }
                 }
             };
@@ -72,6 +72,9 @@ impl GenerateCode for ir::Contract {
                 #[allow(non_snake_case)]
                 pub type #storage_ident = __liquid_private::TestableStorage;
 
+                #[cfg(test)]
+                pub use __liquid_private::methods;
+
 
                 // #(#asset_idents)*
 
@@ -12,7 +12,10 @@
 
 use crate::{
     common::GenerateCode,
-    contract::ir::{Contract, FnArg},
+    contract::{
+        codegen::utils,
+        ir::{Contract, FnArg, FunctionKind},
+    },
     utils as lang_utils,
 };
 use derive_more::From;
@@ -28,6 +31,9 @@ impl<'a> GenerateCode for Testable<'a> {
     fn generate_code(&self) -> TokenStream2 {
         let testable_storage = self.generate_testable_storage();
         let constructor = self.generate_constructor();
+        let deployer = self.generate_deployer();
+        let callers = self.generate_callers();
+        let methods = self.generate_methods();
 
         quote! {
             #[cfg(test)]
@@ -35,14 +41,17 @@ impl<'a> GenerateCode for Testable<'a> {
                 use super::*;
 
                 #testable_storage
+                #methods
 
                 impl TestableStorage {
                     #constructor
+                    #deployer
+                    #callers
                 }
             }
 
             #[cfg(test)]
-            pub use __liquid_testable::TestableStorage;
+            pub use __liquid_testable::{methods, TestableStorage};
         }
     }
 }
@@ -96,9 +105,10 @@ impl<'a> Testable<'a> {
 
         quote! {
             #(#attrs)*
-            pub fn #ident(#(#args)*) -> Self {
+            pub fn #ident(#(#args,)*) -> Self {
+                liquid_lang::env::test::register_current_method_names(__LIQUID_METHOD_NAMES);
                 let mut contract = <Storage as liquid_lang::storage::New>::new();
-                contract.#ident(#(#arg_idents)*);
+                contract.#ident(#(#arg_idents,)*);
                 liquid_lang::storage::reset_mutable_call_flag();
                 Self {
                     contract
@@ -106,4 +116,162 @@ impl<'a> Testable<'a> {
             }
         }
     }
+
+    fn generate_deployer(&self) -> TokenStream2 {
+        let code_register = self.generate_code_register();
+        let constructor = &self.contract.constructor;
+        let ident = &constructor.sig.ident;
+        let inputs = &constructor.sig.inputs;
+        let args = inputs.iter().skip(1);
+        let arg_idents = inputs.iter().skip(1).map(|arg| match arg {
+            FnArg::Typed(ident_type) => &ident_type.ident,
+            _ => unreachable!(),
+        });
+
+        quote! {
+            /// Deploys the contract at `__liquid_address` in the off-chain
+            /// environment, so that it can be called by other contracts via
+            /// `liquid_lang::env::call`.
+            pub fn deploy_at(__liquid_address: liquid_primitives::types::Address, #(#args,)*) {
+                liquid_lang::env::test::register_method_names(__liquid_address, __LIQUID_METHOD_NAMES);
+                liquid_lang::env::test::deploy_contract(__liquid_address, Storage::dispatch, move || {
+                    let mut contract = <Storage as liquid_lang::storage::New>::new();
+                    contract.#ident(#(#arg_idents,)*);
+                    <Storage as liquid_lang::storage::Flush>::flush(&mut contract);
+                });
+                liquid_lang::storage::reset_mutable_call_flag();
+            }
+
+            #code_register
+        }
+    }
+
+    fn generate_code_register(&self) -> TokenStream2 {
+        let sig = &self.contract.constructor.sig;
+        let ident = &sig.ident;
+        let input_tys = utils::generate_input_tys(sig);
+        let input_idents = utils::generate_input_idents(&sig.inputs);
+        let decode = if cfg!(feature = "solidity-compatible") {
+            quote! { liquid_abi_codec::Decode }
+        } else {
+            quote! { scale::Decode }
+        };
+
+        quote! {
+            /// Registers the contract with `code` in the off-chain environment,
+            /// so that other contracts can create it by calling
+            /// `liquid_lang::env::create` with the same code.
+            pub fn register_code(code: &[u8]) {
+                fn construct() -> liquid_lang::DispatchResult {
+                    let call_data = liquid_lang::env::get_call_data(liquid_lang::env::CallMode::Deploy)
+                        .map_err(|_| liquid_lang::DispatchError::CouldNotReadInput)?;
+                    let (#(#input_idents,)*) = <(#(#input_tys,)*) as #decode>::decode(&mut call_data.data.as_slice())
+                        .map_err(|_| liquid_lang::DispatchError::InvalidParams)?;
+                    let mut contract = <Storage as liquid_lang::storage::New>::new();
+                    contract.#ident(#(#input_idents,)*);
+                    <Storage as liquid_lang::storage::Flush>::flush(&mut contract);
+                    Ok(())
+                }
+
+                liquid_lang::env::test::register_code(
+                    code.to_vec(),
+                    construct,
+                    Storage::dispatch,
+                    __LIQUID_METHOD_NAMES,
+                );
+            }
+        }
+    }
+
+    fn generate_methods(&self) -> TokenStream2 {
+        let methods = self.contract.functions.iter().filter_map(|func| {
+            let fn_id = match &func.kind {
+                FunctionKind::External(fn_id, _) => fn_id,
+                _ => return None,
+            };
+            let ident = &func.sig.ident;
+
+            Some(quote! {
+                pub type #ident = super::FnMarker<[(); #fn_id]>;
+            })
+        });
+        let method_names = self
+            .contract
+            .functions
+            .iter()
+            .filter(|func| func.is_external_fn())
+            .map(|func| {
+                let ident = &func.sig.ident;
+                let name = ident.to_string();
+
+                quote! {
+                    (<methods::#ident as liquid_lang::FnSelector>::SELECTOR, #name)
+                }
+            });
+
+        quote! {
+            /// The markers of external methods, which can be used to call
+            /// methods via `TestableStorage::call`.
+            #[allow(non_camel_case_types)]
+            pub mod methods {
+                #(#methods)*
+            }
+
+            /// The names of external methods with their selectors, which are
+            /// shown in execution traces.
+            const __LIQUID_METHOD_NAMES: &[(liquid_primitives::Selector, &str)] = &[
+                #(#method_names,)*
+            ];
+        }
+    }
+
+    fn generate_callers(&self) -> TokenStream2 {
+        let (encode, decode) = if cfg!(feature = "solidity-compatible") {
+            (
+                quote! { liquid_abi_codec::Encode },
+                quote! { liquid_abi_codec::Decode },
+            )
+        } else {
+            (quote! { scale::Encode }, quote! { scale::Decode })
+        };
+
+        quote! {
+            /// Calls the contract with `data`, which consists of a selector and
+            /// encoded arguments, through the dispatcher of the contract, and
+            /// returns the encoded output.
+            ///
+            /// The cached states of the contract will be flushed before the
+            /// call and reloaded after it.
+            pub fn call_raw(&mut self, data: &[u8]) -> Result<Vec<u8>, liquid_lang::DispatchError> {
+                <Storage as liquid_lang::storage::Flush>::flush(&mut self.contract);
+                let result = liquid_lang::env::test::dispatch_call(Storage::dispatch, data);
+                self.contract = <Storage as liquid_lang::storage::New>::new();
+                liquid_lang::storage::reset_mutable_call_flag();
+                result
+            }
+
+            /// Calls the external method marked by `M` with `args` through the
+            /// dispatcher of the contract, and returns the decoded output.
+            ///
+            /// # Panics
+            ///
+            /// If the output can't be decoded as the return type of the method.
+            pub fn call<M>(
+                &mut self,
+                args: <M as liquid_lang::FnInput>::Input,
+            ) -> Result<<M as liquid_lang::FnOutput>::Output, liquid_lang::DispatchError>
+            where
+                M: liquid_lang::FnInput + liquid_lang::FnOutput + liquid_lang::FnSelector,
+                <M as liquid_lang::FnInput>::Input: #encode,
+                <M as liquid_lang::FnOutput>::Output: #decode,
+            {
+                let mut data = <M as liquid_lang::FnSelector>::SELECTOR.to_vec();
+                data.extend(<<M as liquid_lang::FnInput>::Input as #encode>::encode(&args));
+                let output = self.call_raw(&data)?;
+                let output = <<M as liquid_lang::FnOutput>::Output as #decode>::decode(&mut output.as_slice())
+                    .expect("failed to decode the output of the method");
+                Ok(output)
+            }
+        }
+    }
 }
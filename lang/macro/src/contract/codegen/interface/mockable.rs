@@ -10,11 +10,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::{
+    generate_call_data, generate_call_fn, generate_deployer, generate_fn_trait_impls,
+    generate_try_fn_ident,
+};
 use crate::{
     common::GenerateCode,
     contract::{
         codegen::utils as codegen_utils,
-        ir::{FnArg, ForeignFn, Interface},
+        ir::{FnArg, ForeignFn, Interface, LangType},
     },
     utils as lang_utils,
 };
@@ -224,8 +228,13 @@ fn generate_mock_common(foreign_fn: &ForeignFn, suffix: usize) -> TokenStream2 {
     }
 }
 
-fn generate_trivial_fn(foreign_fn: &ForeignFn, interface_ident: &Ident) -> TokenStream2 {
-    let attrs = lang_utils::filter_non_liquid_attributes(foreign_fn.attrs.iter());
+fn generate_trivial_fn(
+    foreign_fn: &ForeignFn,
+    interface_ident: &Ident,
+    is_sol: bool,
+) -> TokenStream2 {
+    let attrs = lang_utils::filter_non_liquid_attributes(foreign_fn.attrs.iter())
+        .collect::<Vec<_>>();
     let sig = &foreign_fn.sig;
     let fn_ident = &sig.ident;
     let span = foreign_fn.span;
@@ -235,14 +244,17 @@ fn generate_trivial_fn(foreign_fn: &ForeignFn, interface_ident: &Ident) -> Token
         None => Ident::new(&format!("{}_context", fn_ident.to_string()), span),
     };
 
+    let try_fn_ident = generate_try_fn_ident(fn_ident);
     let common = generate_mock_common(foreign_fn, 0);
 
     let inputs = &sig.inputs;
     let input_idents = codegen_utils::generate_input_idents(inputs);
-    let no_self_inputs = inputs.iter().skip(1);
+    let no_self_inputs = inputs.iter().skip(1).collect::<Vec<_>>();
 
     let ref_input_idents = input_idents.iter().map(|ident| quote! {&#ident});
     let is_mut = sig.is_mut();
+    let call_fn = generate_call_fn(sig);
+    let call_data = generate_call_data(sig, is_sol, true);
 
     let output = &sig.output;
     let output_ty = match output {
@@ -292,26 +304,48 @@ fn generate_trivial_fn(foreign_fn: &ForeignFn, interface_ident: &Ident) -> Token
                 #(#attrs)*
                 #[allow(non_snake_case)]
                 pub fn #fn_ident(&self, #(#no_self_inputs,)*) -> Option<#output_ty> {
-                    EXPECTATIONS.with(|expectations| {
-                        for expectation in expectations.borrow_mut().iter_mut() {
-                            if expectation.matches(#(#ref_input_idents,)*) {
-                                if #is_mut {
-                                    liquid_lang::storage::mutable_call_happens();
-                                }
-                                return expectation.call(#(#input_idents,)*);
-                            }
+                    self.#try_fn_ident(#(#input_idents,)*).ok()
+                }
+
+                #(#attrs)*
+                #[allow(non_snake_case)]
+                pub fn #try_fn_ident(&self, #(#no_self_inputs,)*) -> ::core::result::Result<#output_ty, liquid_lang::env::CallError> {
+                    let matched = EXPECTATIONS.with(|expectations| {
+                        expectations
+                            .borrow()
+                            .iter()
+                            .position(|expectation| expectation.matches(#(#ref_input_idents,)*))
+                    });
+                    if let Some(i) = matched {
+                        if #is_mut {
+                            liquid_lang::storage::mutable_call_happens();
                         }
+                        // A mocked exception carries no revert data.
+                        return EXPECTATIONS.with(|expectations| {
+                            expectations.borrow_mut()[i].call(#(#input_idents,)*)
+                        }).ok_or_else(Default::default);
+                    }
 
-                        panic!(
-                            "no matched expectation is found for `{}({})` in `{}`",
-                            stringify!(#fn_ident),
-                            stringify!(#inputs)
-                                .replace(" : ", ": ")
-                                .replace("& self", "&self")
-                                .replace("& mut", "&mut"),
-                            stringify!(#interface_ident),
-                        );
-                    })
+                    // Calls the real contract if it had been deployed in the
+                    // off-chain environment.
+                    if liquid_lang::env::test::is_contract_registered(&self.__liquid_address) {
+                        if #is_mut {
+                            liquid_lang::storage::mutable_call_happens();
+                        }
+                        let encoded = #call_data;
+                        return #call_fn::<#output_ty>(&self.__liquid_address, &encoded)
+                            .map_err(Into::into);
+                    }
+
+                    panic!(
+                        "no matched expectation is found for `{}({})` in `{}`",
+                        stringify!(#fn_ident),
+                        stringify!(#inputs)
+                            .replace(" : ", ": ")
+                            .replace("& self", "&self")
+                            .replace("& mut", "&mut"),
+                        stringify!(#interface_ident),
+                    );
                 }
             }
         };
@@ -322,6 +356,7 @@ fn generate_overriding_fn(
     fn_ident: &Ident,
     foreign_fns: &[ForeignFn],
     interface_ident: &Ident,
+    is_sol: bool,
 ) -> TokenStream2 {
     let mock_context_getter = match &foreign_fns[0].mock_context_getter {
         Some(getter) => getter.clone(),
@@ -341,6 +376,7 @@ fn generate_overriding_fn(
         }
     });
 
+    let try_fn_ident = generate_try_fn_ident(fn_ident);
     let overriding_mocks = foreign_fns.iter().enumerate().map(|(i, foreign_fn)| {
         let sig = &foreign_fn.sig;
         let span = foreign_fn.span;
@@ -351,6 +387,8 @@ fn generate_overriding_fn(
 
         let ref_input_idents = input_idents.iter().map(|ident| quote! {&#ident});
         let is_mut = sig.is_mut();
+        let call_fn = generate_call_fn(sig);
+        let call_data = generate_call_data(sig, is_sol, true);
 
         let output = &sig.output;
         let output_ty = match output {
@@ -364,6 +402,18 @@ fn generate_overriding_fn(
 
         let common = generate_mock_common(foreign_fn, i);
         let call_expectation = Ident::new(&format!("call_expectation{}", i), span);
+        let fn_trait_impls = generate_fn_trait_impls(
+            fn_ident,
+            &input_tys,
+            &quote! { Option<#output_ty> },
+            &quote! { #call_expectation(&self.__liquid_address, args).ok() },
+        );
+        let try_fn_trait_impls = generate_fn_trait_impls(
+            &try_fn_ident,
+            &input_tys,
+            &quote! { ::core::result::Result<#output_ty, liquid_lang::env::CallError> },
+            &quote! { #call_expectation(&self.__liquid_address, args) },
+        );
         let expectation = Ident::new(&format!("Expectation{}", i), span);
         let expectations = Ident::new(&format!("EXPECTATIONS{}", i), span);
 
@@ -385,56 +435,65 @@ fn generate_overriding_fn(
                 }
             }
 
-            impl #fn_ident {
-                fn #call_expectation((#(#input_idents,)*): (#(#input_tys,)*)) -> Option<#output_ty> {
-                    #expectations.with(|expectations| {
-                        for expectation in expectations.borrow_mut().iter_mut() {
-                            if expectation.matches(#(#ref_input_idents,)*) {
-                                if #is_mut {
-                                    liquid_lang::storage::mutable_call_happens();
-                                }
-                                return expectation.call(#(#input_idents,)*);
-                            }
-                        }
-
-                        panic!(
-                            "no matched expectation is found for `{}({})` in `{}`",
-                            stringify!(#fn_ident),
-                            stringify!(#inputs)
-                                .replace(" : ", ": ")
-                                .replace("& self", "&self")
-                                .replace("& mut", "&mut"),
-                            stringify!(#interface_ident)
-                        );
-                    })
+            fn #call_expectation(
+                __liquid_address: &liquid_primitives::types::Address,
+                (#(#input_idents,)*): (#(#input_tys,)*),
+            ) -> ::core::result::Result<#output_ty, liquid_lang::env::CallError> {
+                let matched = #expectations.with(|expectations| {
+                    expectations
+                        .borrow()
+                        .iter()
+                        .position(|expectation| expectation.matches(#(#ref_input_idents,)*))
+                });
+                if let Some(i) = matched {
+                    if #is_mut {
+                        liquid_lang::storage::mutable_call_happens();
+                    }
+                    // A mocked exception carries no revert data.
+                    return #expectations.with(|expectations| {
+                        expectations.borrow_mut()[i].call(#(#input_idents,)*)
+                    }).ok_or_else(Default::default);
                 }
-            }
 
-            impl FnOnce<(#(#input_tys,)*)> for #fn_ident {
-                type Output = Option<#output_ty>;
-                extern "rust-call" fn call_once(self, args: (#(#input_tys,)*)) -> Self::Output {
-                    Self::#call_expectation(args)
+                // Calls the real contract if it had been deployed in the
+                // off-chain environment.
+                if liquid_lang::env::test::is_contract_registered(__liquid_address) {
+                    if #is_mut {
+                        liquid_lang::storage::mutable_call_happens();
+                    }
+                    let encoded = #call_data;
+                    return #call_fn::<#output_ty>(__liquid_address, &encoded)
+                        .map_err(Into::into);
                 }
-            }
 
-            impl FnMut<(#(#input_tys,)*)> for #fn_ident {
-                extern "rust-call" fn call_mut(&mut self, args: (#(#input_tys,)*)) -> Self::Output {
-                    Self::#call_expectation(args)
-                }
+                panic!(
+                    "no matched expectation is found for `{}({})` in `{}`",
+                    stringify!(#fn_ident),
+                    stringify!(#inputs)
+                        .replace(" : ", ": ")
+                        .replace("& self", "&self")
+                        .replace("& mut", "&mut"),
+                    stringify!(#interface_ident)
+                );
             }
 
-            impl Fn<(#(#input_tys,)*)> for #fn_ident {
-                extern "rust-call" fn call(&self, args: (#(#input_tys,)*)) -> Self::Output {
-                    Self::#call_expectation(args)
-                }
-            }
+            #fn_trait_impls
+            #try_fn_trait_impls
         }
     });
 
     quote! {
         #[allow(non_camel_case_types)]
         #[derive(Debug, Clone)]
-        pub struct #fn_ident;
+        pub struct #fn_ident {
+            __liquid_address: liquid_primitives::types::Address,
+        }
+
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Clone)]
+        pub struct #try_fn_ident {
+            __liquid_address: liquid_primitives::types::Address,
+        }
 
         const _: () = {
             #(#overriding_mocks)*
@@ -475,25 +534,34 @@ impl<'a> Mockable<'a> {
     fn generate_foreign_contract_mock(&self, interface_ident: &Ident) -> TokenStream2 {
         let interface = self.interface;
         let span = interface.span;
+        let is_sol = matches!(interface.lang_type, LangType::Solidity);
 
         let (trivial_mocks, overriding_fns): (Vec<_>, Vec<_>) =
             interface.foreign_fns.iter().partition_map(|(ident, fns)| {
                 if fns.len() == 1 {
                     let trivial_fn = fns.first().unwrap();
-                    Either::Left(generate_trivial_fn(trivial_fn, interface_ident))
+                    Either::Left(generate_trivial_fn(trivial_fn, interface_ident, is_sol))
                 } else {
                     Either::Right((
-                        ident,
-                        generate_overriding_fn(ident, fns, interface_ident),
+                        vec![ident.clone(), generate_try_fn_ident(ident)],
+                        generate_overriding_fn(ident, fns, interface_ident, is_sol),
                     ))
                 }
             });
         let (overriding_idents, overriding_mocks): (Vec<_>, Vec<_>) =
             overriding_fns.into_iter().unzip();
+        let overriding_idents =
+            overriding_idents.into_iter().flatten().collect::<Vec<_>>();
+        // Creations always happen in the off-chain environment.
+        let deployer = interface
+            .constructor
+            .as_ref()
+            .map(|constructor| generate_deployer(constructor, is_sol));
 
         quote_spanned! { span =>
             #[derive(Debug, Clone)]
             pub struct InterfaceImpl {
+                __liquid_address: liquid_primitives::types::Address,
                 #(
                     pub #overriding_idents: #overriding_idents,
                 )*
@@ -503,13 +571,22 @@ impl<'a> Mockable<'a> {
             pub struct Interface(InterfaceImpl);
 
             impl Interface {
-                pub fn at(_: liquid_primitives::types::Address) -> Self {
+                pub fn at(addr: liquid_primitives::types::Address) -> Self {
                     Self(InterfaceImpl {
+                        __liquid_address: addr,
                         #(
-                            #overriding_idents: #overriding_idents {},
+                            #overriding_idents: #overriding_idents {
+                                __liquid_address: addr,
+                            },
                         )*
                     })
                 }
+
+                /// Returns the interface of the contract registered in CNS with
+                /// `name` and `version`, or `None` if there is no such contract.
+                pub fn at_cns(name: &str, version: &str) -> Option<Self> {
+                    liquid_lang::precompiled::Cns::resolve(name, version).map(Self::at)
+                }
             }
 
             impl From<liquid_primitives::types::Address> for Interface {
@@ -520,18 +597,20 @@ impl<'a> Mockable<'a> {
 
             impl scale::Decode for Interface {
                 fn decode<I: scale::Input>(value: &mut I) -> ::core::result::Result<Self, scale::Error> {
-                    let _ = <() as scale::Decode>::decode(value)?;
-                    Ok(Self(InterfaceImpl {
-                        #(
-                            #overriding_idents: #overriding_idents {},
-                        )*
-                    }))
+                    let addr = <liquid_primitives::types::Address as scale::Decode>::decode(value)?;
+                    Ok(Self::at(addr))
                 }
             }
 
             impl scale::Encode for Interface {
                 fn encode(&self) -> Vec<u8> {
-                    ().encode()
+                    self.0.__liquid_address.encode()
+                }
+            }
+
+            impl Into<liquid_primitives::types::Address> for Interface {
+                fn into(self) -> liquid_primitives::types::Address {
+                    self.0.__liquid_address
                 }
             }
 
@@ -545,6 +624,8 @@ impl<'a> Mockable<'a> {
             #(#trivial_mocks)*
 
             #(#overriding_mocks)*
+
+            #deployer
         }
     }
 }
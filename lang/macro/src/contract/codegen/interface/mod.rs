@@ -16,15 +16,14 @@ use crate::{
     common::GenerateCode,
     contract::{
         codegen::utils as codegen_utils,
-        ir::{ForeignFn, Interface, LangType},
+        ir::{ForeignFn, Interface, LangType, Signature},
     },
     utils as lang_utils,
 };
 use either::Either;
-use heck::ShoutySnakeCase;
 use itertools::Itertools;
 use mockable::Mockable;
-use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use proc_macro2::{Ident, TokenStream as TokenStream2};
 use quote::{quote, quote_spanned};
 
 impl GenerateCode for Interface {
@@ -88,39 +87,38 @@ impl GenerateCode for Interface {
     }
 }
 
-fn generate_selector_ident(fn_name: &Ident) -> Ident {
-    let shouty_name = &fn_name.to_string().to_shouty_snake_case();
-    Ident::new(&shouty_name, Span::call_site())
+/// Generates the path of the function used to call a foreign function, the
+/// ones taking `&self` are called in static mode.
+fn generate_call_fn(sig: &Signature) -> TokenStream2 {
+    if sig.is_mut() {
+        quote! { liquid_lang::env::call }
+    } else {
+        quote! { liquid_lang::env::static_call }
+    }
 }
 
-fn generate_trivial_fn(foreign_fn: &ForeignFn, is_sol: bool) -> TokenStream2 {
-    let attrs = lang_utils::filter_non_liquid_attributes(foreign_fn.attrs.iter());
-    let sig = &foreign_fn.sig;
-    let span = foreign_fn.span;
-    let fn_ident = &sig.ident;
+/// Generates the identifier of the variant of a foreign function which returns
+/// the failure of the call as a `CallError` instead of `None`.
+fn generate_try_fn_ident(fn_name: &Ident) -> Ident {
+    Ident::new(&format!("try_{}", fn_name), fn_name.span())
+}
 
-    let inputs = &sig.inputs;
-    let input_tys = codegen_utils::generate_input_tys(&sig);
+/// Generates an expression which evaluates to the call data of a foreign
+/// function, i.e., the selector followed by the encoded arguments. The call
+/// data passed to constructors has no selector.
+fn generate_call_data(
+    sig: &Signature,
+    is_sol: bool,
+    with_selector: bool,
+) -> TokenStream2 {
+    let input_tys = codegen_utils::generate_input_tys(sig);
     let input_ty_checker = codegen_utils::generate_ty_checker(input_tys.as_slice());
-    let input_idents = codegen_utils::generate_input_idents(inputs);
+    let input_idents = codegen_utils::generate_input_idents(&sig.inputs);
 
-    let output = &sig.output;
-    let output_ty = match output {
-        syn::ReturnType::Default => {
-            quote! { () }
-        }
-        syn::ReturnType::Type(_, ty) => quote! {
-            <#ty as liquid_lang::You_Should_Use_An_Valid_Return_Type>::T
-        },
-    };
-
-    let selector_ident = generate_selector_ident(fn_ident);
-    let fn_name = fn_ident.to_string();
+    let fn_name = sig.ident.to_string();
     let fn_name_bytes = fn_name.as_bytes();
     let fn_name_len = fn_name.len();
 
-    let inputs = inputs.iter().skip(1);
-    let is_mut = sig.is_mut();
     let encode = if !is_sol {
         quote! {
             <Input as scale::Encode>::encode(&(#(#input_idents,)*))
@@ -131,15 +129,23 @@ fn generate_trivial_fn(foreign_fn: &ForeignFn, is_sol: bool) -> TokenStream2 {
         }
     };
 
-    quote_spanned! { span =>
-        #(#attrs)*
-        #[allow(non_snake_case)]
-        pub fn #fn_ident(&self, #(#inputs,)*) -> Option<#output_ty> {
+    if !with_selector {
+        return quote! {
+            {
+                #[allow(dead_code)]
+                type Input = #input_ty_checker;
+
+                #encode
+            }
+        };
+    }
+
+    quote! {
+        {
             #[allow(dead_code)]
             type Input = #input_ty_checker;
 
-            #[allow(dead_code)]
-            const #selector_ident: liquid_primitives::Selector = {
+            const SELECTOR: liquid_primitives::Selector = {
                 const SIG_LEN: usize =
                     liquid_ty_mapping::len::<Input>()
                     + #fn_name_len
@@ -152,13 +158,136 @@ fn generate_trivial_fn(foreign_fn: &ForeignFn, is_sol: bool) -> TokenStream2 {
                 [hash[0], hash[1], hash[2], hash[3]]
             };
 
-            let mut encoded = #selector_ident.to_vec();
+            let mut encoded = SELECTOR.to_vec();
             encoded.extend(#encode);
+            encoded
+        }
+    }
+}
+
+/// Generates the `deploy` function of an interface which declares the
+/// constructor of the foreign contract.
+fn generate_deployer(constructor: &ForeignFn, is_sol: bool) -> TokenStream2 {
+    let attrs = lang_utils::filter_non_liquid_attributes(constructor.attrs.iter());
+    let sig = &constructor.sig;
+    let span = constructor.span;
 
+    let inputs = sig.inputs.iter().skip(1);
+    let call_data = generate_call_data(sig, is_sol, false);
+
+    quote_spanned! { span =>
+        impl Interface {
+            #(#attrs)*
+            /// Creates a contract with `code`, passing the arguments to its
+            /// constructor, and returns the interface of the new contract.
+            pub fn deploy(code: &[u8], #(#inputs,)*) -> ::core::result::Result<Self, liquid_lang::env::CallError> {
+                let encoded = #call_data;
+                liquid_lang::storage::mutable_call_happens();
+                let addr = liquid_lang::env::create(code, &encoded)?;
+                Ok(Self::at(addr))
+            }
+        }
+    }
+}
+
+fn generate_trivial_fn(foreign_fn: &ForeignFn, is_sol: bool) -> TokenStream2 {
+    let attrs = lang_utils::filter_non_liquid_attributes(foreign_fn.attrs.iter())
+        .collect::<Vec<_>>();
+    let sig = &foreign_fn.sig;
+    let span = foreign_fn.span;
+    let fn_ident = &sig.ident;
+
+    let inputs = &sig.inputs;
+    let input_idents = codegen_utils::generate_input_idents(inputs);
+
+    let output = &sig.output;
+    let output_ty = match output {
+        syn::ReturnType::Default => {
+            quote! { () }
+        }
+        syn::ReturnType::Type(_, ty) => quote! {
+            <#ty as liquid_lang::You_Should_Use_An_Valid_Return_Type>::T
+        },
+    };
+
+    let try_fn_ident = generate_try_fn_ident(fn_ident);
+    let inputs = inputs.iter().skip(1).collect::<Vec<_>>();
+    let is_mut = sig.is_mut();
+    let call_fn = generate_call_fn(sig);
+    let call_data = generate_call_data(sig, is_sol, true);
+
+    quote_spanned! { span =>
+        #(#attrs)*
+        #[allow(non_snake_case)]
+        pub fn #fn_ident(&self, #(#inputs,)*) -> Option<#output_ty> {
+            self.#try_fn_ident(#(#input_idents,)*).ok()
+        }
+
+        #(#attrs)*
+        #[allow(non_snake_case)]
+        pub fn #try_fn_ident(&self, #(#inputs,)*) -> ::core::result::Result<#output_ty, liquid_lang::env::CallError> {
+            let encoded = #call_data;
             if #is_mut {
                 liquid_lang::storage::mutable_call_happens();
             }
-            liquid_lang::env::call::<#output_ty>(&self.__liquid_address, &encoded).ok()
+            #call_fn::<#output_ty>(&self.__liquid_address, &encoded)
+                .map_err(Into::into)
+        }
+    }
+}
+
+/// Generates the struct standing for an overloaded foreign function, whose
+/// overloads are called via the `Fn` traits implemented for it.
+fn generate_overriding_struct(ident: &Ident) -> TokenStream2 {
+    quote! {
+        #[allow(non_camel_case_types)]
+        pub struct #ident {
+            __liquid_address: *const liquid_primitives::types::Address,
+        }
+
+        impl #ident {
+            pub fn init(&mut self, addr: *const liquid_primitives::types::Address) {
+                self.__liquid_address = addr;
+            }
+        }
+
+        impl Default for #ident {
+            fn default() -> Self {
+                Self {
+                    __liquid_address: core::ptr::null(),
+                }
+            }
+        }
+    }
+}
+
+/// Implements the `Fn` traits of an overload for the struct `ident`, which
+/// evaluate `call` with the arguments passed as a tuple named `args`.
+fn generate_fn_trait_impls(
+    ident: &Ident,
+    input_tys: &[&syn::Type],
+    output_ty: &TokenStream2,
+    call: &TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        impl FnOnce<(#(#input_tys,)*)> for #ident {
+            type Output = #output_ty;
+
+            extern "rust-call" fn call_once(self, args: (#(#input_tys,)*)) -> Self::Output {
+                #call
+            }
+        }
+
+        impl FnMut<(#(#input_tys,)*)> for #ident {
+            extern "rust-call" fn call_mut(&mut self, args: (#(#input_tys,)*)) -> Self::Output {
+                #call
+            }
+        }
+
+        impl Fn<(#(#input_tys,)*)> for #ident {
+            extern "rust-call" fn call(&self, args: (#(#input_tys,)*)) -> Self::Output {
+                #call
+            }
         }
     }
 }
@@ -168,15 +297,14 @@ fn generate_overriding_fn(
     foreign_fns: &[ForeignFn],
     is_sol: bool,
 ) -> TokenStream2 {
+    let try_fn_ident = generate_try_fn_ident(fn_ident);
     let impls = foreign_fns.iter().enumerate().map(|(i, foreign_fn)| {
         let attrs = lang_utils::filter_non_liquid_attributes(foreign_fn.attrs.iter());
         let sig = &foreign_fn.sig;
         let span = foreign_fn.span;
 
-        let inputs = &sig.inputs;
         let input_tys = codegen_utils::generate_input_tys(&sig);
-        let input_ty_checker = codegen_utils::generate_ty_checker(input_tys.as_slice());
-        let input_idents = codegen_utils::generate_input_idents(inputs);
+        let input_idents = codegen_utils::generate_input_idents(&sig.inputs);
 
         let output = &sig.output;
         let output_ty = match output {
@@ -188,104 +316,52 @@ fn generate_overriding_fn(
             },
         };
 
-        let origin_fn_ident = &sig.ident;
-        let fn_name = format!("{}_{}", origin_fn_ident, i);
-        let fn_ident = Ident::new(&fn_name, span);
-
-        let selector_ident = generate_selector_ident(&fn_ident);
-        let origin_fn_name = origin_fn_ident.to_string();
-        let origin_fn_name_bytes = origin_fn_name.as_bytes();
-        let origin_fn_name_len = origin_fn_name.len();
-
-        let inputs = inputs.iter().skip(1);
+        let overload_ident = Ident::new(&format!("{}_{}", try_fn_ident, i), span);
         let is_mut = sig.is_mut();
-        let encode = if is_sol {
-            quote! {
-                <Input as scale::Encode>::encode(&(#(#input_idents,)*))
-            }
-        } else {
-            quote! {
-                <Input as liquid_abi_codec::Encode>::encode(&(#(#input_idents,)*))
-            }
-        };
+        let call_fn = generate_call_fn(sig);
+        let call_data = generate_call_data(sig, is_sol, true);
+
+        let fn_trait_impls = generate_fn_trait_impls(
+            fn_ident,
+            &input_tys,
+            &quote! { Option<#output_ty> },
+            &quote! {
+                #overload_ident(unsafe { &*self.__liquid_address }, args).ok()
+            },
+        );
+        let try_fn_trait_impls = generate_fn_trait_impls(
+            &try_fn_ident,
+            &input_tys,
+            &quote! { ::core::result::Result<#output_ty, liquid_lang::env::CallError> },
+            &quote! {
+                #overload_ident(unsafe { &*self.__liquid_address }, args)
+            },
+        );
 
         quote_spanned! { span =>
             #[allow(non_snake_case)]
             #(#attrs)*
-            fn #fn_ident(__liquid_address: &liquid_primitives::types::Address, #(#inputs,)*) -> Option<#output_ty> {
-                #[allow(dead_code)]
-                type Input = #input_ty_checker;
-
-                #[allow(dead_code)]
-                const #selector_ident: liquid_primitives::Selector = {
-                    const SIG_LEN: usize =
-                        liquid_ty_mapping::len::<Input>()
-                        + #origin_fn_name_len
-                        + 2;
-
-                    const SIG: [u8; SIG_LEN] =
-                        liquid_ty_mapping::composite::<Input, SIG_LEN>(&[#(#origin_fn_name_bytes),*]);
-
-                    let hash = liquid_primitives::hash::hash(&SIG);
-                    [hash[0], hash[1], hash[2], hash[3]]
-                };
-
-                let mut encoded = #selector_ident.to_vec();
-                encoded.extend(#encode);
-
+            fn #overload_ident(
+                __liquid_address: &liquid_primitives::types::Address,
+                (#(#input_idents,)*): (#(#input_tys,)*),
+            ) -> ::core::result::Result<#output_ty, liquid_lang::env::CallError> {
+                let encoded = #call_data;
                 if #is_mut {
                     liquid_lang::storage::mutable_call_happens();
                 }
-                liquid_lang::env::call::<#output_ty>(&__liquid_address, &encoded).ok()
-            }
-
-            impl FnOnce<(#(#input_tys,)*)> for #origin_fn_ident {
-                type Output = Option<#output_ty>;
-
-                extern "rust-call" fn call_once(self, (#(#input_idents,)*): (#(#input_tys,)*)) -> Self::Output {
-                    #fn_ident(unsafe {
-                        &*self.__liquid_address
-                    }, #(#input_idents,)*)
-                }
+                #call_fn::<#output_ty>(__liquid_address, &encoded).map_err(Into::into)
             }
 
-            impl FnMut<(#(#input_tys,)*)> for #origin_fn_ident {
-                extern "rust-call" fn call_mut(&mut self, (#(#input_idents,)*): (#(#input_tys,)*)) -> Self::Output {
-                    #fn_ident(unsafe {
-                        &*self.__liquid_address
-                    }, #(#input_idents,)*)
-                }
-            }
-
-            impl Fn<(#(#input_tys,)*)> for #origin_fn_ident {
-                extern "rust-call" fn call(&self, (#(#input_idents,)*): (#(#input_tys,)*)) -> Self::Output {
-                    #fn_ident(unsafe {
-                        &*self.__liquid_address
-                    }, #(#input_idents,)*)
-                }
-            }
+            #fn_trait_impls
+            #try_fn_trait_impls
         }
     });
 
+    let fn_struct = generate_overriding_struct(fn_ident);
+    let try_fn_struct = generate_overriding_struct(&try_fn_ident);
     quote! {
-        #[allow(non_camel_case_types)]
-        pub struct #fn_ident {
-            __liquid_address: *const liquid_primitives::types::Address,
-        }
-
-        impl #fn_ident {
-            pub fn init(&mut self, addr: *const liquid_primitives::types::Address) {
-                self.__liquid_address = addr;
-            }
-        }
-
-        impl Default for #fn_ident {
-            fn default() -> Self {
-                Self {
-                    __liquid_address: core::ptr::null(),
-                }
-            }
-        }
+        #fn_struct
+        #try_fn_struct
 
         #(#impls)*
     }
@@ -327,11 +403,18 @@ impl Interface {
                     let trivial_fn = fns.first().unwrap();
                     Either::Left(generate_trivial_fn(trivial_fn, is_sol))
                 } else {
-                    Either::Right((ident, generate_overriding_fn(ident, fns, is_sol)))
+                    let idents = vec![ident.clone(), generate_try_fn_ident(ident)];
+                    Either::Right((idents, generate_overriding_fn(ident, fns, is_sol)))
                 }
             });
         let (overriding_idents, overriding_impls): (Vec<_>, Vec<_>) =
             overriding_fns.into_iter().unzip();
+        let overriding_idents =
+            overriding_idents.into_iter().flatten().collect::<Vec<_>>();
+        let deployer = self
+            .constructor
+            .as_ref()
+            .map(|constructor| generate_deployer(constructor, is_sol));
 
         let type_notations = if cfg!(feature = "solidity-compatible") {
             quote! {
@@ -378,6 +461,12 @@ impl Interface {
 
                     Self(boxed)
                 }
+
+                /// Returns the interface of the contract registered in CNS with
+                /// `name` and `version`, or `None` if there is no such contract.
+                pub fn at_cns(name: &str, version: &str) -> Option<Self> {
+                    liquid_lang::precompiled::Cns::resolve(name, version).map(Self::at)
+                }
             }
 
             impl From<liquid_primitives::types::Address> for Interface {
@@ -401,6 +490,8 @@ impl Interface {
 
             #(#overriding_impls)*
 
+            #deployer
+
             impl Into<liquid_primitives::types::Address> for Interface {
                 fn into(self) -> liquid_primitives::types::Address {
                     self.0.__liquid_address
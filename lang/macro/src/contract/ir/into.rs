@@ -1068,6 +1068,7 @@ impl TryFrom<(ir::InterfaceParams, syn::ItemMod)> for ir::Interface {
 
         let mut foreign_structs = Vec::new();
         let mut foreign_fns = BTreeMap::<_, Vec<ir::ForeignFn>>::new();
+        let mut constructor = None::<ir::ForeignFn>;
         let mut imports = Vec::new();
         let span = item_mod.span();
 
@@ -1089,7 +1090,7 @@ impl TryFrom<(ir::InterfaceParams, syn::ItemMod)> for ir::Interface {
                     imports.push(item_use);
                 }
                 syn::Item::ForeignMod(item_foreign_mod) => {
-                    if !foreign_fns.is_empty() {
+                    if !foreign_fns.is_empty() || constructor.is_some() {
                         bail!(
                             item_foreign_mod,
                             "interface module must have exactly one `extern` block"
@@ -1119,6 +1120,30 @@ impl TryFrom<(ir::InterfaceParams, syn::ItemMod)> for ir::Interface {
                     for foreign_item in item_foreign_mod.items.iter() {
                         let foreign_fn = ir::ForeignFn::try_from(foreign_item)?;
                         let ident = foreign_fn.sig.ident.clone();
+                        if ident == "new" {
+                            if let Some(constructor) = &constructor {
+                                bail_span!(
+                                    constructor.span,
+                                    "duplicate constructor declaration found here"
+                                )
+                            }
+                            if !foreign_fn.sig.is_mut() {
+                                bail_span!(
+                                    foreign_fn.sig.self_arg().span(),
+                                    "`&mut self` is mandatory first parameter for \
+                                     constructor"
+                                )
+                            }
+                            if let syn::ReturnType::Type(..) = foreign_fn.sig.output {
+                                bail!(
+                                    foreign_fn.sig.output,
+                                    "constructor should not have return value"
+                                )
+                            }
+                            constructor = Some(foreign_fn);
+                            continue;
+                        }
+
                         if let Some(fns) = foreign_fns.get_mut(&ident) {
                             if interface_ident == foreign_fn.sig.ident {
                                 bail_span!(
@@ -1153,7 +1178,7 @@ impl TryFrom<(ir::InterfaceParams, syn::ItemMod)> for ir::Interface {
                         }
                     }
 
-                    if foreign_fns.is_empty() {
+                    if foreign_fns.is_empty() && constructor.is_none() {
                         bail!(
                             item_foreign_mod,
                             "at least one declaration of method should be provided in \
@@ -1181,12 +1206,29 @@ impl TryFrom<(ir::InterfaceParams, syn::ItemMod)> for ir::Interface {
             }
         }
 
+        // A `try_` variant is generated for each method, which must not collide
+        // with the methods declared by users.
+        for ident in foreign_fns.keys() {
+            let try_ident = format!("try_{}", ident);
+            if let Some((_, fns)) =
+                foreign_fns.iter().find(|(other, _)| **other == try_ident)
+            {
+                bail_span!(
+                    fns[0].span(),
+                    "the name of this method is reserved for the `try_` variant of \
+                     method `{}`",
+                    ident.to_string()
+                )
+            }
+        }
+
         Ok(Self {
             mod_token: item_mod.mod_token,
             ident: item_mod.ident,
             meta_info,
             foreign_structs,
             foreign_fns,
+            constructor,
             imports,
             interface_ident,
             lang_type,
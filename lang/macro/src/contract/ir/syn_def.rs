@@ -497,6 +497,8 @@ pub struct Interface {
     pub foreign_structs: Vec<ForeignStruct>,
     /// The declarations of methods.
     pub foreign_fns: BTreeMap<Ident, Vec<ForeignFn>>,
+    /// The declaration of the constructor, which is named `new`.
+    pub constructor: Option<ForeignFn>,
     /// The use declarations to import other symbols.
     pub imports: Vec<syn::ItemUse>,
     /// The name of auto-generated interface struct.
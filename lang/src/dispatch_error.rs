@@ -12,7 +12,7 @@
 
 use liquid_prelude::string::String;
 
-#[cfg_attr(feature = "std", derive(Debug))]
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
 pub enum DispatchError {
     UnknownSelector,
     InvalidParams,
@@ -10,11 +10,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::lang_core::env::api;
-use liquid_primitives::types::{timestamp, Address};
+use crate::lang_core::{
+    env::{api, error::EnvError, CallError},
+    precompiled::Crypto,
+};
+use liquid_prelude::string::String;
+use liquid_primitives::types::{timestamp, Address, Bytes32};
 
 pub struct EnvAccess;
 
+/// Unwraps the result of calling a precompiled contract, or reverts if the
+/// call failed.
+fn unwrap_or_revert<T>(value: Option<T>) -> T {
+    match value {
+        Some(value) => value,
+        None => {
+            api::revert(&String::from(
+                "failed to call the crypto precompiled contract",
+            ));
+            unreachable!();
+        }
+    }
+}
+
 impl EnvAccess {
     pub fn get_caller(self) -> Address {
         api::get_caller()
@@ -28,12 +46,82 @@ impl EnvAccess {
         api::now()
     }
 
+    /// Returns the number of current block.
+    pub fn get_block_number(self) -> u64 {
+        api::get_block_number()
+    }
+
     pub fn get_address(self) -> Address {
         api::get_address()
     }
 
+    /// Returns the size of the call data of current call, including the
+    /// selector.
+    pub fn get_call_data_size(self) -> u32 {
+        api::get_call_data_size()
+    }
+
+    /// Returns the number of cross-contract calls in the call chain leading to
+    /// current call, e.g., 0 if the contract is called by a user directly.
+    ///
+    /// # Note
+    ///
+    /// The blockchain doesn't expose the depth of the call chain to contracts,
+    /// so it's only available in the off-chain environment, and
+    /// `EnvError::Unsupported` is returned on-chain.
+    pub fn get_call_depth(self) -> Result<u32, EnvError> {
+        api::get_call_depth()
+    }
+
+    /// Computes the Keccak-256 hash of `data`.
+    pub fn keccak256(self, data: &[u8]) -> Bytes32 {
+        unwrap_or_revert(Crypto::keccak256(data))
+    }
+
+    /// Computes the SM3 hash of `data`.
+    pub fn sm3(self, data: &[u8]) -> Bytes32 {
+        unwrap_or_revert(Crypto::sm3(data))
+    }
+
+    /// Computes the SHA-256 hash of `data`.
+    pub fn sha256(self, data: &[u8]) -> Bytes32 {
+        unwrap_or_revert(Crypto::sha256(data))
+    }
+
+    /// Recovers the address of the account which signed `hash` with a
+    /// secp256k1 signature `(v, r, s)`, returns `None` if the signature is
+    /// invalid.
+    pub fn ecrecover(
+        self,
+        hash: &Bytes32,
+        v: u8,
+        r: &Bytes32,
+        s: &Bytes32,
+    ) -> Option<Address> {
+        Crypto::ecrecover(hash, v, r, s)
+    }
+
+    /// Verifies the SM2 signature `(r, s)` of `hash` against `public_key`,
+    /// returns the address of the signer if the signature is valid.
+    pub fn sm2_verify(
+        self,
+        hash: &Bytes32,
+        public_key: &[u8],
+        r: &Bytes32,
+        s: &Bytes32,
+    ) -> Option<Address> {
+        Crypto::sm2_verify(hash, public_key, r, s)
+    }
+
+    /// Returns whether `account` is a contract account, i.e., it has code.
     #[allow(clippy::wrong_self_convention)]
     pub fn is_contract(self, account: &Address) -> bool {
-        matches!(api::get_external_code_size(account), 0)
+        api::get_external_code_size(account) > 0
+    }
+
+    /// Creates a contract with `code`, passing `data` to its constructor as
+    /// the encoded arguments, and returns the address of the new contract.
+    pub fn create(self, code: &[u8], data: &[u8]) -> Result<Address, CallError> {
+        api::create(code, data).map_err(Into::into)
     }
 }
@@ -18,6 +18,7 @@ use crate::lang_core::env::{
     CallMode,
 };
 use cfg_if::cfg_if;
+use core::fmt;
 use liquid_prelude::{string::String, vec::Vec};
 use liquid_primitives::{
     types::{timestamp, Address},
@@ -76,6 +77,34 @@ pub fn now() -> timestamp {
     <EnvInstance as OnInstance>::on_instance(|instance| Env::now(instance))
 }
 
+pub fn get_block_number() -> u64 {
+    <EnvInstance as OnInstance>::on_instance(|instance| Env::get_block_number(instance))
+}
+
+pub fn get_call_data_size() -> u32 {
+    <EnvInstance as OnInstance>::on_instance(|instance| Env::get_call_data_size(instance))
+}
+
+pub fn get_call_depth() -> Result<u32> {
+    <EnvInstance as OnInstance>::on_instance(|instance| Env::get_call_depth(instance))
+}
+
+/// Creates a contract with `code`, passing `data` to its constructor as the
+/// encoded arguments, and returns the address of the new contract.
+pub fn create(code: &[u8], data: &[u8]) -> Result<Address> {
+    EnvInstance::create_contract(code, data)
+}
+
+/// Formats `args` and prints it via the debug interface of the environment,
+/// which is used by `debug!`.
+pub fn debug_message(args: fmt::Arguments) {
+    let mut message = String::new();
+    let _ = fmt::write(&mut message, args);
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        Env::debug_message(instance, &message)
+    })
+}
+
 pub fn register_asset(
     asset_name: &[u8],
     issuer: &Address,
@@ -148,9 +177,22 @@ cfg_if! {
         where
             R: liquid_abi_codec::Decode + liquid_abi_codec::TypeInfo,
         {
-            <EnvInstance as OnInstance>::on_instance(|instance| {
-                Env::call(instance, addr, data)
-            })
+            EnvInstance::call_contract(addr, data, false)
+        }
+
+        /// Calls the contract at `addr` in static mode, in which the callee
+        /// can't write storage, emit events, move assets or create contracts.
+        ///
+        /// # Note
+        ///
+        /// The blockchain doesn't provide static calls to contracts, so it's
+        /// the same as `call` on-chain, and the restrictions of static mode
+        /// are only enforced in the off-chain environment.
+        pub fn static_call<R>(addr: &Address, data: &[u8]) -> Result<R>
+        where
+            R: liquid_abi_codec::Decode + liquid_abi_codec::TypeInfo,
+        {
+            EnvInstance::call_contract(addr, data, true)
         }
 
         pub fn finish<V>(return_value: &V)
@@ -184,9 +226,22 @@ cfg_if! {
         where
             R: scale::Decode,
         {
-            <EnvInstance as OnInstance>::on_instance(|instance| {
-                Env::call(instance, addr, data)
-            })
+            EnvInstance::call_contract(addr, data, false)
+        }
+
+        /// Calls the contract at `addr` in static mode, in which the callee
+        /// can't write storage, emit events, move assets or create contracts.
+        ///
+        /// # Note
+        ///
+        /// The blockchain doesn't provide static calls to contracts, so it's
+        /// the same as `call` on-chain, and the restrictions of static mode
+        /// are only enforced in the off-chain environment.
+        pub fn static_call<R>(addr: &Address, data: &[u8]) -> Result<R>
+        where
+            R: scale::Decode,
+        {
+            EnvInstance::call_contract(addr, data, true)
         }
 
         pub fn finish<V>(return_value: &V)
@@ -45,7 +45,16 @@ pub trait Env {
 
     fn get_block_number(&mut self) -> u64;
 
+    fn get_call_data_size(&mut self) -> u32;
+
+    fn get_call_depth(&mut self) -> Result<u32>;
+
+    fn debug_message(&mut self, message: &str);
+
     fn get_address(&mut self) -> Address;
+
+    fn create(&mut self, code: &[u8], data: &[u8]) -> Result<Address>;
+
     fn get_external_code_size(&self, account: &Address) -> u32;
     fn register_asset(
         &mut self,
@@ -75,7 +84,7 @@ pub trait Env {
         from_self: bool,
     ) -> bool;
 
-    fn get_asset_balance(&self, to: &Address, asset_name: &[u8]) -> u64;
+    fn get_asset_balance(&mut self, to: &Address, asset_name: &[u8]) -> u64;
 
     fn get_not_fungible_asset_ids(
         &mut self,
@@ -0,0 +1,223 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic accounts with key material for testing purposes.
+//!
+//! The key pairs are secp256k1 key pairs, or SM2 key pairs if the `gm` feature
+//! is enabled. The address of an account is the last 20 bytes of the hash of
+//! its uncompressed public key(without the `0x04` prefix), where the hash is
+//! Keccak-256, or SM3 if the `gm` feature is enabled.
+
+use cfg_if::cfg_if;
+use liquid_primitives::{
+    hash::hash,
+    types::address::{Address, ADDRESS_LENGTH},
+};
+
+pub const SECRET_KEY_LENGTH: usize = 32;
+pub const PUBLIC_KEY_LENGTH: usize = 64;
+
+cfg_if! {
+    if #[cfg(not(feature = "gm"))] {
+        /// A secp256k1 signature.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct Signature {
+            pub r: [u8; 32],
+            pub s: [u8; 32],
+            /// The recovery id plus 27, as expected by `ecrecover`.
+            pub v: u8,
+        }
+
+        impl Signature {
+            /// Returns the signature in the form of `r || s || v`.
+            pub fn to_bytes(&self) -> Vec<u8> {
+                let mut bytes = Vec::with_capacity(65);
+                bytes.extend_from_slice(&self.r);
+                bytes.extend_from_slice(&self.s);
+                bytes.push(self.v);
+                bytes
+            }
+        }
+
+        fn parse_secret_key(
+            secret_key: &[u8; SECRET_KEY_LENGTH],
+        ) -> Option<[u8; PUBLIC_KEY_LENGTH]> {
+            let secret_key = secp256k1::SecretKey::parse(secret_key).ok()?;
+            let public_key = secp256k1::PublicKey::from_secret_key(&secret_key);
+            let mut ret = [0u8; PUBLIC_KEY_LENGTH];
+            ret.copy_from_slice(&public_key.serialize()[1..]);
+            Some(ret)
+        }
+
+        fn sign(account: &Account, message_hash: &[u8; 32]) -> Signature {
+            let secret_key = secp256k1::SecretKey::parse(&account.secret_key).unwrap();
+            let message = secp256k1::Message::parse(message_hash);
+            let (signature, recovery_id) = secp256k1::sign(&message, &secret_key);
+            let signature = signature.serialize();
+            let mut r = [0u8; 32];
+            let mut s = [0u8; 32];
+            r.copy_from_slice(&signature[..32]);
+            s.copy_from_slice(&signature[32..]);
+            Signature {
+                r,
+                s,
+                v: recovery_id.serialize() + 27,
+            }
+        }
+    } else {
+        use libsm::sm2::signature::SigCtx;
+
+        /// The order `n` of the base point of the SM2 curve.
+        const SM2_ORDER: [u8; SECRET_KEY_LENGTH] = [
+            0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0x72, 0x03, 0xdf, 0x6b, 0x21, 0xc6, 0x05, 0x2b, 0x53, 0xbb,
+            0xf4, 0x09, 0x39, 0xd5, 0x41, 0x23,
+        ];
+
+        /// An SM2 signature.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct Signature {
+            pub r: [u8; 32],
+            pub s: [u8; 32],
+            /// The public key of the signer, which is needed to verify the
+            /// signature.
+            pub public_key: [u8; PUBLIC_KEY_LENGTH],
+        }
+
+        impl Signature {
+            /// Returns the signature in the form of `r || s || public_key`.
+            pub fn to_bytes(&self) -> Vec<u8> {
+                let mut bytes = Vec::with_capacity(128);
+                bytes.extend_from_slice(&self.r);
+                bytes.extend_from_slice(&self.s);
+                bytes.extend_from_slice(&self.public_key);
+                bytes
+            }
+        }
+
+        /// Converts a big-endian integer to 32 bytes with leading zeros.
+        fn to_fixed_bytes(bytes: Vec<u8>) -> [u8; 32] {
+            let mut ret = [0u8; 32];
+            ret[32 - bytes.len()..].copy_from_slice(&bytes);
+            ret
+        }
+
+        fn parse_secret_key(
+            secret_key: &[u8; SECRET_KEY_LENGTH],
+        ) -> Option<[u8; PUBLIC_KEY_LENGTH]> {
+            // A valid secret key must be in `[1, n - 1]`, big-endian arrays of
+            // the same length can be compared lexicographically.
+            if secret_key.iter().all(|byte| *byte == 0) || secret_key >= &SM2_ORDER {
+                return None;
+            }
+            let ctx = SigCtx::new();
+            let secret_key = ctx.load_seckey(secret_key).ok()?;
+            let public_key = ctx.pk_from_sk(&secret_key);
+            let mut ret = [0u8; PUBLIC_KEY_LENGTH];
+            ret.copy_from_slice(&ctx.serialize_pubkey(&public_key, false)[1..]);
+            Some(ret)
+        }
+
+        /// Signs `message_hash` as the message, the randomness is not derived
+        /// from the secret key, so the signatures of the same message differ.
+        fn sign(account: &Account, message_hash: &[u8; 32]) -> Signature {
+            let ctx = SigCtx::new();
+            let secret_key = ctx.load_seckey(&account.secret_key).unwrap();
+            let public_key = ctx.pk_from_sk(&secret_key);
+            let signature = ctx.sign(message_hash, &secret_key, &public_key);
+            Signature {
+                r: to_fixed_bytes(signature.get_r().to_bytes_be()),
+                s: to_fixed_bytes(signature.get_s().to_bytes_be()),
+                public_key: account.public_key,
+            }
+        }
+    }
+}
+
+/// An account with its key pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub address: Address,
+    pub secret_key: [u8; SECRET_KEY_LENGTH],
+    /// The uncompressed public key without the `0x04` prefix.
+    pub public_key: [u8; PUBLIC_KEY_LENGTH],
+}
+
+impl Account {
+    /// Creates an account from `secret_key`, returns `None` if it's not a
+    /// valid secret key.
+    pub fn from_secret_key(secret_key: [u8; SECRET_KEY_LENGTH]) -> Option<Self> {
+        let public_key = parse_secret_key(&secret_key)?;
+        let mut address = [0u8; ADDRESS_LENGTH];
+        address.copy_from_slice(&hash(&public_key)[32 - ADDRESS_LENGTH..]);
+        Some(Self {
+            address: Address::new(address),
+            secret_key,
+            public_key,
+        })
+    }
+
+    /// Signs `message_hash`, which is usually the hash of a message computed
+    /// by `liquid_primitives::hash::hash`.
+    pub fn sign(&self, message_hash: &[u8; 32]) -> Signature {
+        sign(self, message_hash)
+    }
+
+    /// Signs the hash of `message`.
+    pub fn sign_message(&self, message: &[u8]) -> Signature {
+        self.sign(&hash(message))
+    }
+}
+
+/// A factory deriving any number of accounts from a seed deterministically,
+/// i.e., the same seed always derives the same accounts in the same order.
+#[derive(Debug, Clone)]
+pub struct AccountFactory {
+    seed: Vec<u8>,
+    next_index: u64,
+}
+
+impl AccountFactory {
+    pub fn new(seed: &[u8]) -> Self {
+        Self {
+            seed: seed.to_vec(),
+            next_index: 0,
+        }
+    }
+
+    /// Derives the account at `index`, whose secret key is the hash of the
+    /// seed followed by the big-endian `index`. The hash is rehashed in the
+    /// rare case that it's not a valid secret key.
+    pub fn account(&self, index: u64) -> Account {
+        let mut material = self.seed.clone();
+        material.extend_from_slice(&index.to_be_bytes());
+        let mut secret_key = hash(&material);
+        loop {
+            if let Some(account) = Account::from_secret_key(secret_key) {
+                return account;
+            }
+            secret_key = hash(&secret_key);
+        }
+    }
+
+    /// Derives the next account which has not been derived by this method.
+    pub fn next_account(&mut self) -> Account {
+        let account = self.account(self.next_index);
+        self.next_index += 1;
+        account
+    }
+
+    /// Derives the first `n` accounts.
+    pub fn accounts(&self, n: u64) -> Vec<Account> {
+        (0..n).map(|index| self.account(index)).collect()
+    }
+}
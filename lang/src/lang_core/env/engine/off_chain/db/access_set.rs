@@ -0,0 +1,139 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::split_storage_key;
+use liquid_primitives::types::Address;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A piece of state which can be accessed by contracts.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StateKey {
+    /// An entry of contract storage. `index` is the part after `$` in the
+    /// storage key, e.g., the encoded key of a `Mapping`, and it's empty if the
+    /// storage key is the field name itself.
+    Storage {
+        address: Address,
+        field: String,
+        index: Vec<u8>,
+    },
+    /// The information of an asset, including its supply.
+    AssetInfo { name: String },
+    /// The balance of an account in the ledger of an asset.
+    AssetBalance { name: String, account: Address },
+}
+
+impl StateKey {
+    pub fn storage(address: Address, key: &[u8]) -> Self {
+        let (field, index) = split_storage_key(key);
+        StateKey::Storage {
+            address,
+            field,
+            index,
+        }
+    }
+
+    /// Returns the storage field name if it's an entry of contract storage.
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            StateKey::Storage { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+
+    /// Decodes the index of a storage entry, e.g., the key of a `Mapping`.
+    pub fn decode_index<K>(&self) -> Option<K>
+    where
+        K: scale::Decode,
+    {
+        match self {
+            StateKey::Storage { index, .. } if !index.is_empty() => {
+                <K as scale::Decode>::decode(&mut index.as_slice()).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The state read and written during an execution.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccessSet {
+    pub reads: BTreeSet<StateKey>,
+    pub writes: BTreeSet<StateKey>,
+}
+
+impl AccessSet {
+    pub fn record_read(&mut self, key: StateKey) {
+        self.reads.insert(key);
+    }
+
+    pub fn record_write(&mut self, key: StateKey) {
+        self.writes.insert(key);
+    }
+
+    pub fn merge(&mut self, other: AccessSet) {
+        self.reads.extend(other.reads);
+        self.writes.extend(other.writes);
+    }
+
+    /// Returns whether `key` is read or written.
+    pub fn accesses(&self, key: &StateKey) -> bool {
+        self.reads.contains(key) || self.writes.contains(key)
+    }
+
+    /// Returns the state on which the two executions conflict, i.e., the
+    /// state written by one of them and accessed by the other one.
+    pub fn conflicts(&self, other: &AccessSet) -> BTreeSet<StateKey> {
+        self.writes
+            .iter()
+            .filter(|key| other.accesses(key))
+            .chain(other.writes.iter().filter(|key| self.accesses(key)))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns whether the two executions can't be executed in parallel.
+    pub fn conflicts_with(&self, other: &AccessSet) -> bool {
+        !self.conflicts(other).is_empty()
+    }
+}
+
+/// Returns the state which serializes all the executions, i.e., every two of
+/// them conflict on it.
+///
+/// # Note
+///
+/// A piece of state serializes all the executions if all of them access it and
+/// at most one of them doesn't write it.
+pub fn serializing_keys(access_sets: &[AccessSet]) -> BTreeSet<StateKey> {
+    if access_sets.len() < 2 {
+        return BTreeSet::new();
+    }
+
+    let mut counts = BTreeMap::<&StateKey, (usize, usize)>::new();
+    for access_set in access_sets {
+        for key in access_set.reads.union(&access_set.writes) {
+            let (accessed, read_only) = counts.entry(key).or_default();
+            *accessed += 1;
+            if !access_set.writes.contains(key) {
+                *read_only += 1;
+            }
+        }
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, (accessed, read_only))| {
+            *accessed == access_sets.len() && *read_only <= 1
+        })
+        .map(|(key, _)| key.clone())
+        .collect()
+}
@@ -10,6 +10,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[derive(Clone)]
 pub struct Block {
     /// The current block number.
     block_number: u64,
@@ -18,17 +19,19 @@ pub struct Block {
 }
 
 impl Block {
-    pub fn new(block_number: u64) -> Self {
-        use chrono::prelude::Utc;
-
-        let date_time = Utc::now();
-        let timestamp = date_time.timestamp() as u64;
+    pub fn new(block_number: u64, timestamp: u64) -> Self {
         Self {
             block_number,
             timestamp,
         }
     }
 
+    /// Creates a genesis block stamped with 0, so that the time observed by
+    /// contracts doesn't depend on when tests run.
+    pub fn genesis() -> Self {
+        Self::new(0, 0)
+    }
+
     /// Returns the block number.
     pub fn block_number(&self) -> u64 {
         self.block_number
@@ -14,6 +14,7 @@ use crate::lang_core::env::error::{EnvError, Result};
 use liquid_prelude::{collections::BTreeMap, vec::Vec};
 use scale::{Decode, Encode};
 
+#[derive(Clone)]
 pub struct ContractStorage {
     entries: BTreeMap<Vec<u8>, Vec<u8>>,
 }
@@ -35,14 +36,33 @@ impl ContractStorage {
         }
     }
 
-    pub fn set_storage<V>(&mut self, key: &[u8], value: &V)
+    /// Sets the value of `key`, and returns the previous one.
+    pub fn set_storage<V>(&mut self, key: &[u8], value: &V) -> Option<Vec<u8>>
     where
         V: Encode,
     {
-        self.entries.insert(key.to_vec(), value.encode());
+        self.entries.insert(key.to_vec(), value.encode())
     }
 
-    pub fn remove_storage(&mut self, key: &[u8]) {
-        self.entries.remove(key);
+    /// Removes `key`, and returns the previous value of it.
+    pub fn remove_storage(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.entries.remove(key)
+    }
+
+    /// Restores the encoded value of `key` to `previous`, which is returned
+    /// by `set_storage` or `remove_storage`.
+    pub fn restore(&mut self, key: Vec<u8>, previous: Option<Vec<u8>>) {
+        match previous {
+            Some(value) => self.entries.insert(key, value),
+            None => self.entries.remove(&key),
+        };
+    }
+
+    pub fn entries(&self) -> &BTreeMap<Vec<u8>, Vec<u8>> {
+        &self.entries
+    }
+
+    pub fn from_entries(entries: BTreeMap<Vec<u8>, Vec<u8>>) -> Self {
+        Self { entries }
     }
 }
@@ -0,0 +1,138 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// The prices of operations performed in the off-chain environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostTable {
+    pub storage_read: u64,
+    pub storage_read_byte: u64,
+    pub storage_write: u64,
+    pub storage_write_byte: u64,
+    pub storage_remove: u64,
+    pub event: u64,
+    pub event_byte: u64,
+    pub event_topic: u64,
+    pub call: u64,
+    pub create: u64,
+    pub create_byte: u64,
+    pub asset_operation: u64,
+}
+
+impl Default for CostTable {
+    fn default() -> Self {
+        Self {
+            storage_read: 200,
+            storage_read_byte: 1,
+            storage_write: 5000,
+            storage_write_byte: 10,
+            storage_remove: 500,
+            event: 375,
+            event_byte: 8,
+            event_topic: 375,
+            call: 700,
+            create: 32000,
+            create_byte: 200,
+            asset_operation: 1000,
+        }
+    }
+}
+
+/// An operation which has cost.
+pub enum Operation {
+    /// Reads a storage entry, the size includes both the key and the value.
+    StorageRead {
+        size: usize,
+    },
+    /// Writes a storage entry, the size includes both the key and the value.
+    StorageWrite {
+        size: usize,
+    },
+    StorageRemove,
+    Event {
+        size: usize,
+        topics: usize,
+    },
+    Call,
+    /// Creates a contract, the size is the length of its code.
+    Create {
+        size: usize,
+    },
+    AssetOperation,
+}
+
+/// The operations performed during an execution and their total cost.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cost {
+    pub storage_reads: u64,
+    pub storage_read_bytes: u64,
+    pub storage_writes: u64,
+    pub storage_write_bytes: u64,
+    pub storage_removals: u64,
+    pub events: u64,
+    pub event_bytes: u64,
+    pub event_topics: u64,
+    pub calls: u64,
+    pub creations: u64,
+    pub created_code_bytes: u64,
+    pub asset_operations: u64,
+    /// The total cost calculated with the cost table.
+    pub total: u64,
+}
+
+impl Cost {
+    pub fn charge(&mut self, operation: &Operation, table: &CostTable) {
+        let cost = match *operation {
+            Operation::StorageRead { size } => {
+                self.storage_reads += 1;
+                self.storage_read_bytes += size as u64;
+                table.storage_read + table.storage_read_byte * size as u64
+            }
+            Operation::StorageWrite { size } => {
+                self.storage_writes += 1;
+                self.storage_write_bytes += size as u64;
+                table.storage_write + table.storage_write_byte * size as u64
+            }
+            Operation::StorageRemove => {
+                self.storage_removals += 1;
+                table.storage_remove
+            }
+            Operation::Event { size, topics } => {
+                self.events += 1;
+                self.event_bytes += size as u64;
+                self.event_topics += topics as u64;
+                table.event
+                    + table.event_byte * size as u64
+                    + table.event_topic * topics as u64
+            }
+            Operation::Call => {
+                self.calls += 1;
+                table.call
+            }
+            Operation::Create { size } => {
+                self.creations += 1;
+                self.created_code_bytes += size as u64;
+                table.create + table.create_byte * size as u64
+            }
+            Operation::AssetOperation => {
+                self.asset_operations += 1;
+                table.asset_operation
+            }
+        };
+        self.total += cost;
+    }
+}
+
+/// Accumulates the cost of an execution, and optionally limits it.
+pub struct Meter {
+    pub cost: Cost,
+    pub budget: Option<u64>,
+}
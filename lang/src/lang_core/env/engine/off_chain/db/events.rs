@@ -11,6 +11,7 @@
 // limitations under the License.
 
 use cfg_if::cfg_if;
+use core::fmt;
 use liquid_primitives::{types::Hash, Topics};
 
 cfg_if! {
@@ -21,7 +22,7 @@ cfg_if! {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Event {
     pub data: Vec<u8>,
     pub topics: Vec<Hash>,
@@ -44,4 +45,28 @@ impl Event {
     {
         <R as Decode>::decode(&mut self.data.as_slice()).unwrap()
     }
+
+    /// Returns the signature of the event, i.e., the first topic of it.
+    pub fn signature(&self) -> Option<&Hash> {
+        self.topics.first()
+    }
+}
+
+impl fmt::Debug for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let topics = self
+            .topics
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        let data = self
+            .data
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        f.debug_struct("Event")
+            .field("topics", &topics)
+            .field("data", &format_args!("0x{}", data))
+            .finish()
+    }
 }
@@ -12,17 +12,35 @@
 
 use liquid_primitives::types::Address;
 
+#[derive(Clone)]
 pub struct ExecContext {
     /// The caller of the contract execution.
     ///
     /// Might be user or another contract.
     pub caller: Address,
     pub callee: Address,
+    /// The input data of the contract execution.
+    pub call_data: Vec<u8>,
+    /// The output data set by `finish` during the contract execution.
+    pub return_data: Vec<u8>,
+    /// The number of cross-contract calls in the call chain leading to the
+    /// contract execution, i.e., 0 if the contract is called by a user.
+    pub depth: u32,
+    /// Whether the contract execution is in a static call, in which the state
+    /// can't be mutated.
+    pub is_static: bool,
 }
 
 impl ExecContext {
     pub fn new(caller: Address, callee: Address) -> Self {
-        Self { caller, callee }
+        Self {
+            caller,
+            callee,
+            call_data: Vec::new(),
+            return_data: Vec::new(),
+            depth: 0,
+            is_static: false,
+        }
     }
 
     pub fn caller(&self) -> Address {
@@ -0,0 +1,225 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{Event, StateKey};
+use core::fmt;
+use liquid_primitives::{types::Address, Selector};
+
+fn to_hex(bytes: &[u8]) -> String {
+    let digits = bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    format!("0x{}", digits)
+}
+
+/// A step of an execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceStep {
+    /// A call to another contract, or to the dispatcher of the contract being
+    /// tested.
+    Call(CallTrace),
+    StorageRead {
+        key: StateKey,
+    },
+    StorageWrite {
+        key: StateKey,
+        /// The encoded value being written.
+        value: Vec<u8>,
+    },
+    StorageRemove {
+        key: StateKey,
+    },
+    Event(Event),
+    /// The point where the execution reverts.
+    Revert {
+        reason: String,
+    },
+}
+
+/// The trace of a call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallTrace {
+    pub caller: Address,
+    pub callee: Address,
+    /// The selector in the call data, `None` if the call is a deployment or
+    /// the call data is too short to contain a selector.
+    pub selector: Option<Selector>,
+    /// The name of the method being called if it's known, e.g., the callee is
+    /// deployed via `deploy_at`. It's `new` for deployments.
+    pub method: Option<String>,
+    pub steps: Vec<TraceStep>,
+    /// Whether the call is reverted or panicked.
+    pub reverted: bool,
+}
+
+impl CallTrace {
+    pub fn new(
+        caller: Address,
+        callee: Address,
+        selector: Option<Selector>,
+        method: Option<String>,
+    ) -> Self {
+        Self {
+            caller,
+            callee,
+            selector,
+            method,
+            steps: Vec::new(),
+            reverted: false,
+        }
+    }
+}
+
+/// The structured trace of an execution in the off-chain environment, which
+/// consists of nested calls, storage operations, events and the revert point.
+///
+/// The trace can be printed as an indented tree via `Display`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExecTrace {
+    pub steps: Vec<TraceStep>,
+}
+
+/// Visits `steps` and the steps of nested calls in execution order.
+fn walk<'a>(steps: &'a [TraceStep], visit: &mut dyn FnMut(&'a TraceStep)) {
+    for step in steps {
+        visit(step);
+        if let TraceStep::Call(call) = step {
+            walk(&call.steps, visit);
+        }
+    }
+}
+
+impl ExecTrace {
+    /// Returns all the calls in execution order, nested calls included.
+    pub fn calls(&self) -> Vec<&CallTrace> {
+        let mut calls = Vec::new();
+        walk(&self.steps, &mut |step| {
+            if let TraceStep::Call(call) = step {
+                calls.push(call);
+            }
+        });
+        calls
+    }
+
+    /// Returns the calls to the method named `method` in execution order.
+    pub fn calls_of(&self, method: &str) -> Vec<&CallTrace> {
+        self.calls()
+            .into_iter()
+            .filter(|call| call.method.as_deref() == Some(method))
+            .collect()
+    }
+
+    /// Returns all the events emitted in execution order, including the ones
+    /// discarded due to reverts.
+    pub fn events(&self) -> Vec<&Event> {
+        let mut events = Vec::new();
+        walk(&self.steps, &mut |step| {
+            if let TraceStep::Event(event) = step {
+                events.push(event);
+            }
+        });
+        events
+    }
+
+    /// Returns the storage entries written or removed in execution order.
+    pub fn storage_writes(&self) -> Vec<&StateKey> {
+        let mut keys = Vec::new();
+        walk(&self.steps, &mut |step| match step {
+            TraceStep::StorageWrite { key, .. } | TraceStep::StorageRemove { key } => {
+                keys.push(key)
+            }
+            _ => (),
+        });
+        keys
+    }
+
+    /// Returns the reason of the first revert during the execution.
+    pub fn revert_reason(&self) -> Option<&str> {
+        let mut reason = None;
+        walk(&self.steps, &mut |step| {
+            if let TraceStep::Revert { reason: r } = step {
+                reason.get_or_insert(r.as_str());
+            }
+        });
+        reason
+    }
+}
+
+struct DisplayKey<'a>(&'a StateKey);
+
+impl<'a> fmt::Display for DisplayKey<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            StateKey::Storage { field, index, .. } if index.is_empty() => {
+                write!(f, "{}", field)
+            }
+            StateKey::Storage { field, index, .. } => {
+                write!(f, "{}[{}]", field, to_hex(index))
+            }
+            key => write!(f, "{:?}", key),
+        }
+    }
+}
+
+fn fmt_steps(f: &mut fmt::Formatter, steps: &[TraceStep], depth: usize) -> fmt::Result {
+    let indent = "  ".repeat(depth);
+    for step in steps {
+        match step {
+            TraceStep::Call(call) => {
+                write!(f, "{}call {} -> {}", indent, call.caller, call.callee)?;
+                if let Some(method) = &call.method {
+                    write!(f, " {}", method)?;
+                }
+                if let Some(selector) = &call.selector {
+                    write!(f, " ({})", to_hex(selector))?;
+                }
+                if call.reverted {
+                    write!(f, " [reverted]")?;
+                }
+                writeln!(f)?;
+                fmt_steps(f, &call.steps, depth + 1)?;
+            }
+            TraceStep::StorageRead { key } => {
+                writeln!(f, "{}read {}", indent, DisplayKey(key))?
+            }
+            TraceStep::StorageWrite { key, value } => {
+                writeln!(f, "{}write {} = {}", indent, DisplayKey(key), to_hex(value))?
+            }
+            TraceStep::StorageRemove { key } => {
+                writeln!(f, "{}remove {}", indent, DisplayKey(key))?
+            }
+            TraceStep::Event(event) => {
+                let topics = event
+                    .topics
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                writeln!(
+                    f,
+                    "{}event [{}] {}",
+                    indent,
+                    topics.join(", "),
+                    to_hex(&event.data)
+                )?
+            }
+            TraceStep::Revert { reason } => writeln!(f, "{}revert {:?}", indent, reason)?,
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for ExecTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_steps(f, &self.steps, 0)
+    }
+}
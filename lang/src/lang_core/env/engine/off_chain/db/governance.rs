@@ -0,0 +1,53 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lang_core::precompiled::{TX_COUNT_LIMIT, TX_GAS_LIMIT};
+use liquid_primitives::types::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The role of a node in the consensus of the group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeType {
+    Sealer,
+    Observer,
+}
+
+/// The state of the permission, system config and consensus precompiled
+/// contracts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Governance {
+    /// The accounts allowed to write each table, indexed by table names.
+    pub table_permissions: BTreeMap<String, BTreeSet<Address>>,
+    /// The accounts allowed to write each contract, indexed by contract
+    /// addresses.
+    pub contract_permissions: BTreeMap<Address, BTreeSet<Address>>,
+    pub system_configs: BTreeMap<String, String>,
+    /// The sealers and observers of the group, indexed by node IDs.
+    pub consensus_nodes: BTreeMap<String, NodeType>,
+}
+
+impl Default for Governance {
+    /// The system configs default to the ones of a newly built chain.
+    fn default() -> Self {
+        let system_configs = [(TX_COUNT_LIMIT, "1000"), (TX_GAS_LIMIT, "300000000")]
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        Self {
+            table_permissions: BTreeMap::new(),
+            contract_permissions: BTreeMap::new(),
+            system_configs,
+            consensus_nodes: BTreeMap::new(),
+        }
+    }
+}
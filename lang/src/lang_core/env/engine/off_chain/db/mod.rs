@@ -10,12 +10,46 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod access_set;
 mod block;
+mod cns;
 mod contract_storage;
+mod cost;
 mod events;
 mod exec_context;
+mod exec_trace;
+mod governance;
+mod shared;
+mod storage_diff;
+mod tables;
 
+pub use access_set::{serializing_keys, AccessSet, StateKey};
 pub use block::Block;
+pub use cns::CnsEntry;
 pub use contract_storage::ContractStorage;
+pub use cost::{Cost, CostTable, Meter, Operation};
 pub use events::Event;
 pub use exec_context::ExecContext;
+pub use exec_trace::{CallTrace, ExecTrace, TraceStep};
+pub use governance::{Governance, NodeType};
+pub use shared::Shared;
+pub use storage_diff::{EntryChange, FieldDiff, StorageDiff};
+pub use tables::{
+    CompareOp, ConditionData, Fields, KvTableData, TableData, TableObject, TableStore,
+};
+
+/// The separator between the field name and the index in storage keys, the
+/// same as the one used by `TypedChunk`.
+const SEP: u8 = b'$';
+
+/// Splits a storage key into the field name and the index. Field names are
+/// identifiers, so the first `$` must be the separator.
+fn split_storage_key(key: &[u8]) -> (String, Vec<u8>) {
+    match key.iter().position(|byte| *byte == SEP) {
+        Some(pos) => (
+            String::from_utf8_lossy(&key[..pos]).into_owned(),
+            key[pos + 1..].to_vec(),
+        ),
+        None => (String::from_utf8_lossy(key).into_owned(), Vec::new()),
+    }
+}
@@ -0,0 +1,125 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{split_storage_key, ContractStorage};
+use liquid_primitives::types::Address;
+use std::collections::{BTreeMap, HashMap};
+
+/// The change of a storage entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryChange {
+    /// The index of the entry within its storage field, i.e., the part after
+    /// `$` in the storage key. It's empty if the key is the field name itself.
+    pub index: Vec<u8>,
+    /// The encoded value before the change, `None` if the entry is added.
+    pub old: Option<Vec<u8>>,
+    /// The encoded value after the change, `None` if the entry is removed.
+    pub new: Option<Vec<u8>>,
+}
+
+/// The changes of a storage field.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub added: Vec<EntryChange>,
+    pub removed: Vec<EntryChange>,
+    pub changed: Vec<EntryChange>,
+}
+
+impl FieldDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The changes of contract storage between two states of the off-chain
+/// environment, grouped by contract address and then by storage field name.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StorageDiff {
+    pub contracts: BTreeMap<Address, BTreeMap<String, FieldDiff>>,
+}
+
+impl StorageDiff {
+    pub fn new(
+        old: &HashMap<Address, ContractStorage>,
+        new: &HashMap<Address, ContractStorage>,
+    ) -> Self {
+        let empty = ContractStorage::new();
+        let mut diff = Self::default();
+        for address in old.keys().chain(new.keys()) {
+            if diff.contracts.contains_key(address) {
+                continue;
+            }
+
+            let old_storage = old.get(address).unwrap_or(&empty);
+            let new_storage = new.get(address).unwrap_or(&empty);
+            let fields = Self::diff_storage(old_storage, new_storage);
+            if !fields.is_empty() {
+                diff.contracts.insert(*address, fields);
+            }
+        }
+        diff
+    }
+
+    fn diff_storage(
+        old: &ContractStorage,
+        new: &ContractStorage,
+    ) -> BTreeMap<String, FieldDiff> {
+        let mut fields = BTreeMap::<String, FieldDiff>::new();
+        for (key, old_value) in old.entries() {
+            let (field, index) = split_storage_key(key);
+            let field_diff = fields.entry(field).or_default();
+            match new.entries().get(key) {
+                None => field_diff.removed.push(EntryChange {
+                    index,
+                    old: Some(old_value.clone()),
+                    new: None,
+                }),
+                Some(new_value) if new_value != old_value => {
+                    field_diff.changed.push(EntryChange {
+                        index,
+                        old: Some(old_value.clone()),
+                        new: Some(new_value.clone()),
+                    })
+                }
+                _ => (),
+            }
+        }
+
+        for (key, new_value) in new.entries() {
+            if old.entries().contains_key(key) {
+                continue;
+            }
+
+            let (field, index) = split_storage_key(key);
+            fields.entry(field).or_default().added.push(EntryChange {
+                index,
+                old: None,
+                new: Some(new_value.clone()),
+            });
+        }
+
+        fields.retain(|_, field_diff| !field_diff.is_empty());
+        fields
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+    }
+
+    /// Returns the changes of the storage field named `field` of the contract
+    /// at `address`, if any.
+    pub fn field(&self, address: &Address, field: &str) -> Option<&FieldDiff> {
+        self.contracts
+            .get(address)
+            .and_then(|fields| fields.get(field))
+    }
+}
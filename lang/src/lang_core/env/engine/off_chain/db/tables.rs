@@ -0,0 +1,197 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use liquid_primitives::types::{
+    address::{Address, ADDRESS_LENGTH},
+    i256,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The fields of an entry, values are stored as strings the same as FISCO
+/// BCOS does.
+pub type Fields = BTreeMap<String, String>;
+
+/// A KV table created via the KV table factory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KvTableData {
+    pub primary_key: String,
+    /// The value fields, excluding the primary key.
+    pub fields: Vec<String>,
+    /// The rows indexed by their primary keys.
+    pub rows: BTreeMap<String, Fields>,
+}
+
+impl KvTableData {
+    /// Returns whether `field` is the primary key or a value field.
+    pub fn has_field(&self, field: &str) -> bool {
+        self.primary_key == field || self.fields.iter().any(|f| f == field)
+    }
+}
+
+/// A CRUD table created via the table factory, in which a key may be shared
+/// by multiple rows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableData {
+    pub key_field: String,
+    /// The value fields, excluding the key field.
+    pub fields: Vec<String>,
+    /// The rows in the order of insertion.
+    pub rows: Vec<Fields>,
+}
+
+impl TableData {
+    /// Returns whether `field` is the key field or a value field.
+    pub fn has_field(&self, field: &str) -> bool {
+        self.key_field == field || self.fields.iter().any(|f| f == field)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// The condition used to filter the rows of a CRUD table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConditionData {
+    /// The comparisons between fields and values, all of them must be
+    /// satisfied.
+    pub comparisons: Vec<(CompareOp, String, String)>,
+    /// The offset and the maximum number of rows.
+    pub limit: Option<(usize, usize)>,
+}
+
+impl ConditionData {
+    /// Returns whether `row` satisfies all comparisons. `EQ` and `NE` compare
+    /// values as strings, while the others compare values as integers and
+    /// are never satisfied by values which are not integers.
+    pub fn matches(&self, row: &Fields) -> bool {
+        self.comparisons.iter().all(|(op, field, value)| {
+            let actual = row.get(field).map(String::as_str).unwrap_or_default();
+            match op {
+                CompareOp::Eq => actual == value,
+                CompareOp::Ne => actual != value,
+                _ => {
+                    let (actual, value) =
+                        match (actual.parse::<i256>(), value.parse::<i256>()) {
+                            (Ok(actual), Ok(value)) => (actual, value),
+                            _ => return false,
+                        };
+                    match op {
+                        CompareOp::Gt => actual > value,
+                        CompareOp::Ge => actual >= value,
+                        CompareOp::Lt => actual < value,
+                        _ => actual <= value,
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns the indices of the rows of `table` whose key is `key` and
+    /// which satisfy the condition, with the limit applied.
+    pub fn filter(&self, table: &TableData, key: &str) -> Vec<usize> {
+        let matched = table
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| {
+                row.get(&table.key_field).map(String::as_str) == Some(key)
+                    && self.matches(row)
+            })
+            .map(|(index, _)| index);
+        match self.limit {
+            Some((offset, count)) => matched.skip(offset).take(count).collect(),
+            None => matched.collect(),
+        }
+    }
+}
+
+/// The objects returned by the table precompiled contracts, which are called
+/// like contracts at their own addresses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TableObject {
+    /// An opened KV table, referred to by its name.
+    KvTable(String),
+    /// An opened CRUD table, referred to by its name.
+    Table(String),
+    Entry(Fields),
+    Condition(ConditionData),
+    /// The rows selected from a CRUD table.
+    Entries(Vec<Fields>),
+}
+
+/// The KV tables and CRUD tables together with the objects opened by
+/// contracts.
+#[derive(Debug, Clone, Default)]
+pub struct TableStore {
+    /// The KV tables indexed by their names.
+    pub kv_tables: BTreeMap<String, KvTableData>,
+    /// The CRUD tables indexed by their names.
+    pub tables: BTreeMap<String, TableData>,
+    objects: HashMap<Address, TableObject>,
+    /// The serial number of the next object.
+    next_object: u64,
+}
+
+impl TableStore {
+    /// The prefix of the addresses of objects, followed by the serial number
+    /// of each object.
+    const OBJECT_ADDRESS_PREFIX: [u8; ADDRESS_LENGTH - 8] = [0xff; ADDRESS_LENGTH - 8];
+
+    /// Allocates an address for `object`.
+    pub fn insert_object(&mut self, object: TableObject) -> Address {
+        let mut address = [0u8; ADDRESS_LENGTH];
+        address[..ADDRESS_LENGTH - 8].copy_from_slice(&Self::OBJECT_ADDRESS_PREFIX);
+        address[ADDRESS_LENGTH - 8..].copy_from_slice(&self.next_object.to_be_bytes());
+        self.next_object += 1;
+
+        let address = Address::new(address);
+        self.objects.insert(address, object);
+        address
+    }
+
+    pub fn is_object(&self, address: &Address) -> bool {
+        self.objects.contains_key(address)
+    }
+
+    pub fn object(&self, address: &Address) -> Option<&TableObject> {
+        self.objects.get(address)
+    }
+
+    pub fn object_mut(&mut self, address: &Address) -> Option<&mut TableObject> {
+        self.objects.get_mut(address)
+    }
+
+    /// Returns the objects indexed by their addresses, together with the
+    /// serial number of the next object.
+    pub fn objects(&self) -> (&HashMap<Address, TableObject>, u64) {
+        (&self.objects, self.next_object)
+    }
+
+    /// Replaces the objects with `objects`, and allocates the following
+    /// objects from the serial number `next_object`.
+    pub fn set_objects(
+        &mut self,
+        objects: HashMap<Address, TableObject>,
+        next_object: u64,
+    ) {
+        self.objects = objects;
+        self.next_object = next_object;
+    }
+}
@@ -10,47 +10,224 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod accounts;
 mod db;
+mod precompiled;
+mod state;
+#[macro_use]
 pub mod test_api;
 
-use self::db::{Block, ContractStorage, Event, ExecContext};
-use crate::lang_core::env::{
-    backend::Env, calldata::CallData, engine::OnInstance, error::Result, CallMode,
+#[cfg(test)]
+mod tests;
+
+use self::db::{
+    AccessSet, Block, CallTrace, CnsEntry, ContractStorage, Cost, CostTable, Event,
+    ExecContext, ExecTrace, Governance, Meter, Operation, Shared, StateKey, StorageDiff,
+    TableStore, TraceStep,
+};
+use crate::{
+    lang_core::env::{
+        backend::Env,
+        calldata::CallData,
+        engine::OnInstance,
+        error::{CallError, CallErrorKind, EnvError, Result},
+        CallMode,
+    },
+    DispatchResult,
 };
 use cfg_if::cfg_if;
 use core::cell::RefCell;
-use liquid_primitives::{types::address::Address, Topics};
-use std::{collections::HashMap, str};
-
-struct AssetInfo {
-    issuer: Address,
-    fungible: bool,
-    total_supply: u64,
-    _description: String,
-    supplied: u64,
+use liquid_primitives::{
+    types::address::{Address, ADDRESS_LENGTH},
+    Selector, Topics,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    panic::{self, AssertUnwindSafe},
+    str,
+};
+
+/// The entry of a contract deployed in the off-chain environment, which reads
+/// call data via `get_call_data` and writes its output via `finish`.
+pub type Dispatcher = fn() -> DispatchResult;
+
+/// The entries of a contract type which can be created by other contracts in
+/// the off-chain environment.
+#[derive(Clone, Copy)]
+struct CodeEntry {
+    /// Reads the encoded arguments via `get_call_data` and runs the
+    /// constructor with them.
+    constructor: Dispatcher,
+    dispatcher: Dispatcher,
+    /// The names of external methods with their selectors.
+    method_names: &'static [(Selector, &'static str)],
+}
+
+/// The interval in seconds between the timestamps of two adjacent blocks, if
+/// the timestamp of the next block is not specified.
+const BLOCK_INTERVAL: u64 = 1;
+
+/// The simulated code of contracts registered without code, which is the
+/// header of an empty Wasm module.
+const DEFAULT_CONTRACT_CODE: &[u8] = b"\0asm\x01\0\0\0";
+
+/// The information of an asset registered in the off-chain environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetInfo {
+    pub issuer: Address,
+    pub fungible: bool,
+    pub total_supply: u64,
+    pub description: String,
+    /// The amount of fungible asset or the number of not fungible tokens
+    /// which have been issued.
+    pub supplied: u64,
+}
+
+/// The not fungible tokens owned by each account, indexed by asset name, then
+/// by owner and then by token id.
+type NotFungibleAssets = HashMap<String, HashMap<Address, HashMap<u64, String>>>;
+
+/// A change of contract storage made in a transaction, which will be undone
+/// if the transaction reverts.
+struct StorageChange {
+    address: Address,
+    key: Vec<u8>,
+    /// The encoded value before the change.
+    previous: Option<Vec<u8>>,
+}
+
+/// The state of the off-chain environment other than contract storage and
+/// events, which is cheap to clone since the larger parts of it are shared
+/// until mutated.
+#[derive(Clone)]
+struct SharedState {
+    assets_info: Shared<HashMap<String, AssetInfo>>,
+    fungible_asset: Shared<HashMap<String, HashMap<Address, u64>>>,
+    not_fungible_asset: Shared<NotFungibleAssets>,
+    exec_contexts: Vec<ExecContext>,
+    codes: Shared<HashMap<Address, Vec<u8>>>,
+    tables: Shared<TableStore>,
+    cns: Shared<BTreeMap<(String, String), CnsEntry>>,
+    governance: Shared<Governance>,
+    contracts: Shared<HashMap<Address, Dispatcher>>,
+    create_nonces: Shared<HashMap<Address, u64>>,
+    code_entries: Shared<HashMap<Vec<u8>, CodeEntry>>,
+    method_names: Shared<HashMap<Address, HashMap<Selector, String>>>,
+}
+
+/// The state of the off-chain environment which is restored by test.
+#[derive(Clone)]
+struct Snapshot {
+    contract_storages: HashMap<Address, ContractStorage>,
+    events: Vec<Event>,
+    shared: SharedState,
+    blocks: Vec<Block>,
+    next_block_number: Option<u64>,
+    next_block_timestamp: Option<u64>,
+    debug_messages: Vec<String>,
+}
+
+/// The state of the off-chain environment when a transaction begins, which
+/// will be restored if the transaction reverts. Instead of being copied,
+/// contract storage and events are restored by undoing the changes made after
+/// the checkpoint.
+struct Checkpoint {
+    /// The number of storage changes recorded before the transaction.
+    storage_changes: usize,
+    /// The number of events emitted before the transaction.
+    events: usize,
+    shared: SharedState,
 }
 
 pub struct EnvInstance {
-    contract_storage: ContractStorage,
+    contract_storages: HashMap<Address, ContractStorage>,
+    contracts: Shared<HashMap<Address, Dispatcher>>,
+    /// The contract types which can be created by contracts, indexed by their
+    /// code.
+    code_entries: Shared<HashMap<Vec<u8>, CodeEntry>>,
+    /// The number of contracts created by each account, which is used to
+    /// derive the addresses of new contracts.
+    create_nonces: Shared<HashMap<Address, u64>>,
+    /// The code of contract accounts, accounts without code are externally
+    /// owned accounts.
+    codes: Shared<HashMap<Address, Vec<u8>>>,
     blocks: Vec<Block>,
+    /// The number of the next block, if specified by test.
+    next_block_number: Option<u64>,
+    /// The timestamp of the next block, if specified by test.
+    next_block_timestamp: Option<u64>,
     exec_contexts: Vec<ExecContext>,
     events: Vec<Event>,
-    assets_info: HashMap<String, AssetInfo>,
-    fungible_asset: HashMap<String, HashMap<Address, u64>>,
-    not_fungible_asset: HashMap<String, HashMap<Address, HashMap<u64, String>>>,
+    assets_info: Shared<HashMap<String, AssetInfo>>,
+    fungible_asset: Shared<HashMap<String, HashMap<Address, u64>>>,
+    not_fungible_asset: Shared<NotFungibleAssets>,
+    /// The state of the table precompiled contracts.
+    tables: Shared<TableStore>,
+    /// The contracts registered in CNS, indexed by their names and versions.
+    cns: Shared<BTreeMap<(String, String), CnsEntry>>,
+    /// The state of the permission, system config and consensus precompiled
+    /// contracts.
+    governance: Shared<Governance>,
+    /// The changes of contract storage made in the transactions being
+    /// executed, the latest one is the last.
+    storage_changes: Vec<StorageChange>,
+    /// The number of transactions being executed.
+    transactions: usize,
+    /// The reason of the latest revert, which has not been handled yet.
+    revert_reason: Option<String>,
+    /// The snapshots taken by test, indexed by their names.
+    snapshots: HashMap<String, Snapshot>,
+    /// The access sets being recorded, the inner ones are merged into the
+    /// outer ones once finished.
+    access_traces: Vec<AccessSet>,
+    cost_table: CostTable,
+    /// The meters being used, every one of them will be charged for each
+    /// operation.
+    meters: Vec<Meter>,
+    /// The names of the methods of contracts, indexed by contract address and
+    /// then by selector.
+    method_names: Shared<HashMap<Address, HashMap<Selector, String>>>,
+    /// The calls being traced, the innermost one is the last. Once finished,
+    /// a call is appended to the steps of the call enclosing it.
+    call_traces: Vec<CallTrace>,
+    /// The number of calls being traced when each tracing started.
+    trace_bases: Vec<usize>,
+    /// The messages printed via `debug!`, which are kept even if the execution
+    /// printing them reverts.
+    debug_messages: Vec<String>,
 }
 
 impl Default for EnvInstance {
     fn default() -> Self {
-        let blocks = vec![Block::new(0)];
+        let blocks = vec![Block::genesis()];
         Self {
-            contract_storage: ContractStorage::new(),
+            contract_storages: HashMap::new(),
+            contracts: Shared::default(),
+            code_entries: Shared::default(),
+            create_nonces: Shared::default(),
+            codes: Shared::default(),
             blocks,
+            next_block_number: None,
+            next_block_timestamp: None,
             exec_contexts: Vec::new(),
             events: Vec::new(),
-            assets_info: HashMap::new(),
-            fungible_asset: HashMap::new(),
-            not_fungible_asset: HashMap::new(),
+            assets_info: Shared::default(),
+            fungible_asset: Shared::default(),
+            not_fungible_asset: Shared::default(),
+            tables: Shared::default(),
+            cns: Shared::default(),
+            governance: Shared::default(),
+            storage_changes: Vec::new(),
+            transactions: 0,
+            revert_reason: None,
+            snapshots: HashMap::new(),
+            access_traces: Vec::new(),
+            cost_table: CostTable::default(),
+            meters: Vec::new(),
+            method_names: Shared::default(),
+            call_traces: Vec::new(),
+            trace_bases: Vec::new(),
+            debug_messages: Vec::new(),
         }
     }
 }
@@ -62,6 +239,12 @@ impl EnvInstance {
             .expect("there must be at least one execution context in test environment")
     }
 
+    pub fn current_exec_context_mut(&mut self) -> &mut ExecContext {
+        self.exec_contexts
+            .last_mut()
+            .expect("there must be at least one execution context in test environment")
+    }
+
     pub fn first_exec_context(&self) -> &ExecContext {
         self.exec_contexts
             .first()
@@ -74,9 +257,680 @@ impl EnvInstance {
             .expect("there must be at least one block in test environment")
     }
 
+    /// Produces `n` blocks on top of current block, only the last one of them
+    /// will be recorded.
+    fn advance_blocks(&mut self, n: u64) {
+        if n == 0 {
+            return;
+        }
+
+        let current_block = self.current_block();
+        let (current_number, current_timestamp) =
+            (current_block.block_number(), current_block.timestamp());
+        let block_number = self.next_block_number.take().unwrap_or(current_number + n);
+        let timestamp = self
+            .next_block_timestamp
+            .take()
+            .unwrap_or(current_timestamp + n * BLOCK_INTERVAL);
+        self.blocks.push(Block::new(block_number, timestamp));
+    }
+
     pub fn get_events(&self) -> std::slice::Iter<Event> {
         self.events.iter()
     }
+
+    /// Returns the address of the contract being executed. If there is no
+    /// execution context, the zero address will be returned.
+    fn current_address(&self) -> Address {
+        self.exec_contexts
+            .last()
+            .map(|exec_context| exec_context.self_address())
+            .unwrap_or_default()
+    }
+
+    /// Returns the depth of current execution context, or 0 if there is none.
+    fn current_depth(&self) -> u32 {
+        self.exec_contexts
+            .last()
+            .map_or(0, |exec_context| exec_context.depth)
+    }
+
+    /// Returns whether current execution context is in a static call.
+    fn is_static(&self) -> bool {
+        self.exec_contexts
+            .last()
+            .map_or(false, |exec_context| exec_context.is_static)
+    }
+
+    /// Reverts current execution with `reason`.
+    fn abort(&mut self, reason: String) -> ! {
+        self.trace_step(TraceStep::Revert {
+            reason: reason.clone(),
+        });
+        self.revert_reason = Some(reason.clone());
+        panic!("{}", reason);
+    }
+
+    /// Reverts current execution if it's in a static call, since `operation`
+    /// mutates the state.
+    fn ensure_mutable(&mut self, operation: &str) {
+        if self.is_static() {
+            self.abort(format!("{} is not allowed in static call", operation));
+        }
+    }
+
+    /// Returns the storage of the contract being executed, every contract has
+    /// its own isolated storage.
+    fn current_storage_mut(&mut self) -> &mut ContractStorage {
+        let address = self.current_address();
+        self.contract_storages
+            .entry(address)
+            .or_insert_with(ContractStorage::new)
+    }
+
+    fn take_shared_state(&self) -> SharedState {
+        SharedState {
+            assets_info: self.assets_info.clone(),
+            fungible_asset: self.fungible_asset.clone(),
+            not_fungible_asset: self.not_fungible_asset.clone(),
+            exec_contexts: self.exec_contexts.clone(),
+            codes: self.codes.clone(),
+            tables: self.tables.clone(),
+            cns: self.cns.clone(),
+            governance: self.governance.clone(),
+            contracts: self.contracts.clone(),
+            create_nonces: self.create_nonces.clone(),
+            code_entries: self.code_entries.clone(),
+            method_names: self.method_names.clone(),
+        }
+    }
+
+    fn restore_shared_state(&mut self, state: SharedState) {
+        self.assets_info = state.assets_info;
+        self.fungible_asset = state.fungible_asset;
+        self.not_fungible_asset = state.not_fungible_asset;
+        self.exec_contexts = state.exec_contexts;
+        self.codes = state.codes;
+        self.tables = state.tables;
+        self.cns = state.cns;
+        self.governance = state.governance;
+        self.contracts = state.contracts;
+        self.create_nonces = state.create_nonces;
+        self.code_entries = state.code_entries;
+        self.method_names = state.method_names;
+    }
+
+    fn take_snapshot(&self) -> Snapshot {
+        Snapshot {
+            contract_storages: self.contract_storages.clone(),
+            events: self.events.clone(),
+            shared: self.take_shared_state(),
+            blocks: self.blocks.clone(),
+            next_block_number: self.next_block_number,
+            next_block_timestamp: self.next_block_timestamp,
+            debug_messages: self.debug_messages.clone(),
+        }
+    }
+
+    fn restore_snapshot(&mut self, snapshot: Snapshot) {
+        self.contract_storages = snapshot.contract_storages;
+        self.events = snapshot.events;
+        self.restore_shared_state(snapshot.shared);
+        self.blocks = snapshot.blocks;
+        self.next_block_number = snapshot.next_block_number;
+        self.next_block_timestamp = snapshot.next_block_timestamp;
+        self.debug_messages = snapshot.debug_messages;
+    }
+
+    /// Begins a transaction, and returns the checkpoint to restore if the
+    /// transaction reverts.
+    fn begin_transaction(&mut self) -> Checkpoint {
+        self.transactions += 1;
+        Checkpoint {
+            storage_changes: self.storage_changes.len(),
+            events: self.events.len(),
+            shared: self.take_shared_state(),
+        }
+    }
+
+    /// Ends the innermost transaction. Once the outermost one ends, the
+    /// storage changes recorded are no longer needed.
+    fn end_transaction(&mut self) {
+        self.transactions -= 1;
+        if self.transactions == 0 {
+            self.storage_changes.clear();
+        }
+    }
+
+    /// Ends the innermost transaction and discards all changes made by it.
+    fn rollback_transaction(&mut self, checkpoint: Checkpoint) {
+        let changes = self.storage_changes.split_off(checkpoint.storage_changes);
+        for change in changes.into_iter().rev() {
+            self.contract_storages
+                .entry(change.address)
+                .or_insert_with(ContractStorage::new)
+                .restore(change.key, change.previous);
+        }
+        self.events.truncate(checkpoint.events);
+        self.restore_shared_state(checkpoint.shared);
+        self.end_transaction();
+    }
+
+    /// Records the change of `key` in the storage of current contract, whose
+    /// previous value is `previous`, so that the change can be undone if the
+    /// transaction making it reverts.
+    fn record_storage_change(&mut self, key: &[u8], previous: Option<Vec<u8>>) {
+        if self.transactions > 0 {
+            let address = self.current_address();
+            self.storage_changes.push(StorageChange {
+                address,
+                key: key.to_vec(),
+                previous,
+            });
+        }
+    }
+
+    /// Returns the snapshot named `name`.
+    ///
+    /// # Panics
+    ///
+    /// If there is no such snapshot.
+    fn named_snapshot(&self, name: &str) -> &Snapshot {
+        self.snapshots
+            .get(name)
+            .unwrap_or_else(|| panic!("there is no snapshot named `{}`", name))
+    }
+
+    /// Computes the changes of contract storage since the snapshot named `name`
+    /// was taken.
+    fn diff_since(&self, name: &str) -> StorageDiff {
+        StorageDiff::new(
+            &self.named_snapshot(name).contract_storages,
+            &self.contract_storages,
+        )
+    }
+
+    fn record_read(&mut self, key: StateKey) {
+        if let Some(access_set) = self.access_traces.last_mut() {
+            access_set.record_read(key);
+        }
+    }
+
+    fn record_write(&mut self, key: StateKey) {
+        if let Some(access_set) = self.access_traces.last_mut() {
+            access_set.record_write(key);
+        }
+    }
+
+    fn record_storage_access(&mut self, key: &[u8], write: bool) {
+        if self.access_traces.is_empty() {
+            return;
+        }
+
+        let key = StateKey::storage(self.current_address(), key);
+        if write {
+            self.record_write(key);
+        } else {
+            self.record_read(key);
+        }
+    }
+
+    fn record_asset_access(
+        &mut self,
+        name: &str,
+        account: Option<&Address>,
+        write: bool,
+    ) {
+        if self.access_traces.is_empty() {
+            return;
+        }
+
+        let key = match account {
+            Some(account) => StateKey::AssetBalance {
+                name: name.to_owned(),
+                account: *account,
+            },
+            None => StateKey::AssetInfo {
+                name: name.to_owned(),
+            },
+        };
+        self.record_read(key.clone());
+        if write {
+            self.record_write(key);
+        }
+    }
+
+    /// Executes `f` while recording the state read and written by it.
+    fn trace_access<F, R>(f: F) -> (R, AccessSet)
+    where
+        F: FnOnce() -> R,
+    {
+        <Self as OnInstance>::on_instance(|instance| {
+            instance.access_traces.push(AccessSet::default())
+        });
+        let ret = f();
+        let access_set = <Self as OnInstance>::on_instance(|instance| {
+            let access_set = instance
+                .access_traces
+                .pop()
+                .expect("the access set must be pushed before");
+            if let Some(outer) = instance.access_traces.last_mut() {
+                outer.merge(access_set.clone());
+            }
+            access_set
+        });
+        (ret, access_set)
+    }
+
+    fn is_tracing(&self) -> bool {
+        !self.call_traces.is_empty()
+    }
+
+    fn trace_step(&mut self, step: TraceStep) {
+        if let Some(call) = self.call_traces.last_mut() {
+            call.steps.push(step);
+        }
+    }
+
+    /// Starts tracing a call from `caller` to `callee` with `data` as call
+    /// data, `data` is `None` for deployments.
+    fn enter_call_trace(
+        &mut self,
+        caller: Address,
+        callee: Address,
+        data: Option<&[u8]>,
+    ) {
+        if !self.is_tracing() {
+            return;
+        }
+
+        let (selector, method) = match data {
+            Some(data) if data.len() >= 4 => {
+                let mut selector = Selector::default();
+                selector.copy_from_slice(&data[..4]);
+                let method = self
+                    .method_names
+                    .get(&callee)
+                    .and_then(|names| names.get(&selector))
+                    .cloned();
+                (Some(selector), method)
+            }
+            Some(_) => (None, None),
+            None => (None, Some(String::from("new"))),
+        };
+        self.call_traces
+            .push(CallTrace::new(caller, callee, selector, method));
+    }
+
+    /// Finishes tracing the innermost call, which must be started after the
+    /// current tracing.
+    fn leave_call_trace(&mut self, reverted: bool) {
+        let base = match self.trace_bases.last() {
+            Some(base) => *base,
+            None => return,
+        };
+        // The outermost call of a tracing is the execution itself.
+        if self.call_traces.len() <= base + 1 {
+            return;
+        }
+
+        let mut call = self.call_traces.pop().unwrap();
+        call.reverted = reverted;
+        self.trace_step(TraceStep::Call(call));
+    }
+
+    /// Executes `f` while tracing the calls, storage operations, events and
+    /// reverts during it.
+    ///
+    /// # Note
+    ///
+    /// If `f` panics, the trace recorded is discarded and the panic is
+    /// propagated.
+    fn trace_execution<F, R>(f: F) -> (R, ExecTrace)
+    where
+        F: FnOnce() -> R,
+    {
+        <Self as OnInstance>::on_instance(|instance| {
+            let (caller, callee) = instance
+                .exec_contexts
+                .last()
+                .map(|exec_context| (exec_context.caller(), exec_context.self_address()))
+                .unwrap_or_default();
+            instance.trace_bases.push(instance.call_traces.len());
+            instance
+                .call_traces
+                .push(CallTrace::new(caller, callee, None, None));
+        });
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let trace = <Self as OnInstance>::on_instance(|instance| {
+            // The calls left unfinished must be interrupted by panics.
+            while instance.call_traces.len() > instance.trace_bases.last().unwrap() + 1 {
+                instance.leave_call_trace(true);
+            }
+            instance.trace_bases.pop();
+            let steps = instance
+                .call_traces
+                .pop()
+                .expect("the trace must be pushed before")
+                .steps;
+            if let Some(outer) = instance.call_traces.last_mut() {
+                outer.steps.extend(steps.iter().cloned());
+            }
+            ExecTrace { steps }
+        });
+        match result {
+            Ok(ret) => (ret, trace),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    /// Inserts a new asset with an empty ledger.
+    fn insert_asset(&mut self, asset_name: &str, asset_info: AssetInfo) {
+        if asset_info.fungible {
+            self.fungible_asset
+                .insert(asset_name.to_string(), HashMap::new());
+        } else {
+            self.not_fungible_asset
+                .insert(asset_name.to_string(), HashMap::new());
+        }
+        self.assets_info.insert(asset_name.to_string(), asset_info);
+    }
+
+    fn is_metering(&self) -> bool {
+        !self.meters.is_empty()
+    }
+
+    /// Charges all the meters being used for `operation`.
+    ///
+    /// # Panics
+    ///
+    /// If the cost exceeds the budget of any meter.
+    fn charge(&mut self, operation: Operation) {
+        let cost_table = &self.cost_table;
+        for meter in self.meters.iter_mut() {
+            meter.cost.charge(&operation, cost_table);
+            if let Some(budget) = meter.budget {
+                assert!(
+                    meter.cost.total <= budget,
+                    "out of budget: the cost {} exceeds the budget {}",
+                    meter.cost.total,
+                    budget
+                );
+            }
+        }
+    }
+
+    /// Executes `f` with a new meter, and returns the cost of it.
+    fn metered<F, R>(budget: Option<u64>, f: F) -> (R, Cost)
+    where
+        F: FnOnce() -> R,
+    {
+        <Self as OnInstance>::on_instance(|instance| {
+            instance.meters.push(Meter {
+                cost: Cost::default(),
+                budget,
+            })
+        });
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let meter = <Self as OnInstance>::on_instance(|instance| {
+            instance
+                .meters
+                .pop()
+                .expect("the meter must be pushed before")
+        });
+        match result {
+            Ok(ret) => (ret, meter.cost),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    /// Executes `f` as a transaction. If `f` reverts, all changes made by it
+    /// to contract storage, events and assets will be discarded, the execution
+    /// contexts pushed by it will be popped, and the revert reason will be
+    /// returned.
+    ///
+    /// # Note
+    ///
+    /// Panics not caused by `revert` will be propagated as is.
+    fn transact<F, R>(f: F) -> core::result::Result<R, String>
+    where
+        F: FnOnce() -> R,
+    {
+        let (checkpoint, traced_calls) = <Self as OnInstance>::on_instance(|instance| {
+            instance.revert_reason = None;
+            (instance.begin_transaction(), instance.call_traces.len())
+        });
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(ret) => {
+                <Self as OnInstance>::on_instance(Self::end_transaction);
+                Ok(ret)
+            }
+            Err(payload) => {
+                let reason = <Self as OnInstance>::on_instance(|instance| {
+                    let reason = instance.revert_reason.take();
+                    if reason.is_none() {
+                        instance.end_transaction();
+                        return None;
+                    }
+                    instance.rollback_transaction(checkpoint);
+                    while instance.call_traces.len() > traced_calls {
+                        instance.leave_call_trace(true);
+                    }
+                    reason
+                });
+                match reason {
+                    Some(reason) => Err(reason),
+                    None => panic::resume_unwind(payload),
+                }
+            }
+        }
+    }
+
+    /// Enters a new execution context to call the contract at `addr`, and
+    /// returns the dispatcher of the callee, which might be a precompiled
+    /// contract. Calls made in a static call are static as well.
+    fn enter_call(
+        &mut self,
+        addr: &Address,
+        data: &[u8],
+        is_static: bool,
+    ) -> Result<Dispatcher> {
+        self.charge(Operation::Call);
+        let dispatcher = self
+            .contracts
+            .get(addr)
+            .copied()
+            .or_else(|| precompiled::dispatcher_of(self, addr))
+            .ok_or_else(|| EnvError::FailToCallForeignContract(CallError::default()))?;
+        let caller = self.current_address();
+        self.enter_call_trace(caller, *addr, Some(data));
+        let mut exec_context = ExecContext::new(caller, *addr);
+        exec_context.call_data = data.to_vec();
+        exec_context.depth = self.current_depth() + 1;
+        exec_context.is_static = is_static || self.is_static();
+        self.exec_contexts.push(exec_context);
+        Ok(dispatcher)
+    }
+
+    /// Leaves the execution context entered by `enter_call`, and returns
+    /// the output of the callee.
+    fn leave_call(&mut self, result: DispatchResult) -> Result<Vec<u8>> {
+        let exec_context = self
+            .exec_contexts
+            .pop()
+            .expect("there must be at least one execution context in test environment");
+        self.leave_call_trace(result.is_err());
+        match result {
+            Ok(_) => Ok(exec_context.return_data),
+            Err(_) => Err(EnvError::FailToCallForeignContract(
+                CallError::from_return_data(exec_context.return_data),
+            )),
+        }
+    }
+
+    /// Calls the contract registered at `addr` and returns its raw output. If
+    /// `is_static` is true, the call fails once the callee tries to mutate the
+    /// state.
+    ///
+    /// # Note
+    ///
+    /// The dispatcher of the callee accesses the environment by itself, so it
+    /// must be executed outside of `on_instance`.
+    fn call_raw(addr: &Address, data: &[u8], is_static: bool) -> Result<Vec<u8>> {
+        let dispatcher = <Self as OnInstance>::on_instance(|instance| {
+            instance.enter_call(addr, data, is_static)
+        })?;
+        match Self::transact(dispatcher) {
+            Ok(result) => {
+                <Self as OnInstance>::on_instance(|instance| instance.leave_call(result))
+            }
+            Err(reason) => {
+                <Self as OnInstance>::on_instance(|instance| {
+                    instance.exec_contexts.pop();
+                    instance.leave_call_trace(true);
+                });
+                Err(EnvError::FailToCallForeignContract(CallError {
+                    kind: CallErrorKind::Reverted,
+                    return_data: Self::encode_revert_reason(&reason),
+                    reason: Some(reason),
+                }))
+            }
+        }
+    }
+
+    /// Derives the address of the next contract created by `creator` from the
+    /// address and the nonce of it, then increases the nonce.
+    fn derive_contract_address(&mut self, creator: &Address) -> Address {
+        let nonce = self.create_nonces.entry(*creator).or_insert(0);
+        let mut preimage = creator.0.to_vec();
+        preimage.extend_from_slice(&nonce.to_be_bytes());
+        *nonce += 1;
+
+        let hash = liquid_primitives::hash::hash(&preimage);
+        let mut address = [0u8; ADDRESS_LENGTH];
+        address.copy_from_slice(&hash[hash.len() - ADDRESS_LENGTH..]);
+        Address::new(address)
+    }
+
+    /// Enters a new execution context to create a contract with `code` at a
+    /// derived address, and returns the address with the entries of the
+    /// contract type.
+    fn enter_create(&mut self, code: &[u8], data: &[u8]) -> Result<(Address, CodeEntry)> {
+        self.ensure_mutable("contract creation");
+        self.charge(Operation::Create { size: code.len() });
+        let entry = self
+            .code_entries
+            .get(code)
+            .copied()
+            .ok_or_else(|| EnvError::FailToCreateContract(CallError::default()))?;
+        let caller = self.current_address();
+        let address = self.derive_contract_address(&caller);
+        self.enter_call_trace(caller, address, None);
+        let mut exec_context = ExecContext::new(caller, address);
+        exec_context.call_data = data.to_vec();
+        exec_context.depth = self.current_depth() + 1;
+        self.exec_contexts.push(exec_context);
+        Ok((address, entry))
+    }
+
+    /// Leaves the execution context entered by `enter_create`, and registers
+    /// the contract created if the constructor succeeded.
+    fn leave_create(
+        &mut self,
+        code: &[u8],
+        address: Address,
+        entry: CodeEntry,
+        result: DispatchResult,
+    ) -> Result<Address> {
+        let exec_context = self
+            .exec_contexts
+            .pop()
+            .expect("there must be at least one execution context in test environment");
+        self.leave_call_trace(result.is_err());
+        match result {
+            Ok(_) => {
+                self.contracts.insert(address, entry.dispatcher);
+                self.codes.insert(address, code.to_vec());
+                let method_names = self.method_names.entry(address).or_default();
+                for (selector, name) in entry.method_names {
+                    method_names.insert(*selector, (*name).to_owned());
+                }
+                Ok(address)
+            }
+            Err(_) => Err(EnvError::FailToCreateContract(CallError::from_return_data(
+                exec_context.return_data,
+            ))),
+        }
+    }
+
+    /// Creates a contract with `code` which must have been registered via
+    /// `test_api::register_code`, and returns the address of it.
+    ///
+    /// # Note
+    ///
+    /// The same as `call_raw`, the constructor must be executed outside of
+    /// `on_instance`.
+    pub(crate) fn create_contract(code: &[u8], data: &[u8]) -> Result<Address> {
+        let (address, entry) = <Self as OnInstance>::on_instance(|instance| {
+            instance.enter_create(code, data)
+        })?;
+        match Self::transact(entry.constructor) {
+            Ok(result) => <Self as OnInstance>::on_instance(|instance| {
+                instance.leave_create(code, address, entry, result)
+            }),
+            Err(reason) => {
+                <Self as OnInstance>::on_instance(|instance| {
+                    instance.exec_contexts.pop();
+                    instance.leave_call_trace(true);
+                });
+                Err(EnvError::FailToCreateContract(CallError {
+                    kind: CallErrorKind::Reverted,
+                    return_data: Self::encode_revert_reason(&reason),
+                    reason: Some(reason),
+                }))
+            }
+        }
+    }
+
+    /// Encodes `reason` the same way as `revert` does on chain.
+    fn encode_revert_reason(reason: &str) -> Vec<u8> {
+        cfg_if! {
+            if #[cfg(feature = "solidity-compatible")] {
+                liquid_abi_codec::Encode::encode(&reason.to_owned())
+            } else {
+                scale::Encode::encode(reason)
+            }
+        }
+    }
+
+    cfg_if! {
+        if #[cfg(feature = "solidity-compatible")] {
+            pub(crate) fn call_contract<R>(
+                addr: &Address,
+                data: &[u8],
+                is_static: bool,
+            ) -> Result<R>
+            where
+                R: liquid_abi_codec::Decode + liquid_abi_codec::TypeInfo,
+            {
+                let return_data = Self::call_raw(addr, data, is_static)?;
+                liquid_abi_codec::Decode::decode(&mut return_data.as_slice())
+                    .map_err(Into::into)
+            }
+        } else {
+            pub(crate) fn call_contract<R>(
+                addr: &Address,
+                data: &[u8],
+                is_static: bool,
+            ) -> Result<R>
+            where
+                R: scale::Decode,
+            {
+                let return_data = Self::call_raw(addr, data, is_static)?;
+                scale::Decode::decode(&mut return_data.as_slice()).map_err(Into::into)
+            }
+        }
+    }
 }
 
 impl Env for EnvInstance {
@@ -84,22 +938,73 @@ impl Env for EnvInstance {
     where
         V: scale::Encode,
     {
-        self.contract_storage.set_storage(key, value);
+        self.ensure_mutable("writing storage");
+        if self.is_metering() {
+            let size = key.len() + value.encode().len();
+            self.charge(Operation::StorageWrite { size });
+        }
+        self.record_storage_access(key, true);
+        if self.is_tracing() {
+            let key = StateKey::storage(self.current_address(), key);
+            let value = value.encode();
+            self.trace_step(TraceStep::StorageWrite { key, value });
+        }
+        let previous = self.current_storage_mut().set_storage(key, value);
+        self.record_storage_change(key, previous);
     }
 
     fn get_storage<R>(&mut self, key: &[u8]) -> Result<R>
     where
         R: scale::Decode,
     {
-        self.contract_storage.get_storage::<R>(key)
+        if self.is_metering() {
+            let size = key.len()
+                + self
+                    .current_storage_mut()
+                    .entries()
+                    .get(key)
+                    .map_or(0, Vec::len);
+            self.charge(Operation::StorageRead { size });
+        }
+        self.record_storage_access(key, false);
+        if self.is_tracing() {
+            let key = StateKey::storage(self.current_address(), key);
+            self.trace_step(TraceStep::StorageRead { key });
+        }
+        self.current_storage_mut().get_storage::<R>(key)
     }
 
     fn remove_storage(&mut self, key: &[u8]) {
-        self.contract_storage.remove_storage(key);
+        self.ensure_mutable("removing storage");
+        self.charge(Operation::StorageRemove);
+        self.record_storage_access(key, true);
+        if self.is_tracing() {
+            let key = StateKey::storage(self.current_address(), key);
+            self.trace_step(TraceStep::StorageRemove { key });
+        }
+        let previous = self.current_storage_mut().remove_storage(key);
+        self.record_storage_change(key, previous);
     }
 
-    fn get_call_data(&mut self, _: CallMode) -> Result<CallData> {
-        unimplemented!();
+    fn get_call_data(&mut self, mode: CallMode) -> Result<CallData> {
+        let call_data = &self.current_exec_context().call_data;
+        if mode == CallMode::Call {
+            #[cfg(feature = "solidity-compatible")]
+            use liquid_abi_codec::Decode;
+            #[cfg(not(feature = "solidity-compatible"))]
+            use scale::Decode;
+
+            // The call data of external methods must have a selector.
+            if call_data.len() < 4 {
+                return Err(EnvError::UnableToReadCallData);
+            }
+            CallData::decode(&mut call_data.as_slice()).map_err(Into::into)
+        } else {
+            Ok(CallData {
+                selector: [0x00; 4],
+                data: call_data.clone(),
+            })
+        }
     }
 
     fn get_caller(&mut self) -> Address {
@@ -114,8 +1019,12 @@ impl Env for EnvInstance {
         self.current_exec_context().self_address()
     }
 
-    fn get_external_code_size(&self, _account: &Address) -> u32 {
-        unimplemented!();
+    fn create(&mut self, _code: &[u8], _data: &[u8]) -> Result<Address> {
+        unreachable!("contract creations are performed by `EnvInstance::create_contract`")
+    }
+
+    fn get_external_code_size(&self, account: &Address) -> u32 {
+        self.codes.get(account).map_or(0, |code| code.len() as u32)
     }
 
     fn now(&mut self) -> u64 {
@@ -126,27 +1035,49 @@ impl Env for EnvInstance {
         self.current_block().block_number()
     }
 
+    fn get_call_data_size(&mut self) -> u32 {
+        self.current_exec_context().call_data.len() as u32
+    }
+
+    fn get_call_depth(&mut self) -> Result<u32> {
+        Ok(self.current_exec_context().depth)
+    }
+
+    fn debug_message(&mut self, message: &str) {
+        println!("{}", message);
+        self.debug_messages.push(message.to_owned());
+    }
+
     cfg_if! {
         if #[cfg(feature = "solidity-compatible")] {
             fn emit<E>(&mut self, event: E)
             where
                 E: Topics + liquid_abi_codec::Encode,
             {
-                self.events.push(Event::new(event));
+                self.ensure_mutable("emitting events");
+                let event = Event::new(event);
+                self.charge(Operation::Event {
+                    size: event.data.len(),
+                    topics: event.topics.len(),
+                });
+                if self.is_tracing() {
+                    self.trace_step(TraceStep::Event(event.clone()));
+                }
+                self.events.push(event);
             }
 
             fn call<R>(&mut self, _addr: &Address, _data: &[u8]) -> Result<R>
             where
                 R: liquid_abi_codec::Decode + liquid_abi_codec::TypeInfo,
             {
-                unimplemented!();
+                unreachable!("cross-contract calls are performed by `EnvInstance::call_contract`")
             }
 
-            fn finish<V>(&mut self, _: &V)
+            fn finish<V>(&mut self, return_value: &V)
             where
                 V: liquid_abi_codec::Encode,
             {
-                unimplemented!();
+                self.current_exec_context_mut().return_data = return_value.encode();
             }
 
             fn revert<V>(&mut self, msg: &V)
@@ -154,31 +1085,41 @@ impl Env for EnvInstance {
                 V: liquid_abi_codec::Encode,
             {
                 // Ensure that the type of `V` can only be String.
-                panic!("{}", <String as liquid_abi_codec::Decode>::decode(
+                let reason = <String as liquid_abi_codec::Decode>::decode(
                     &mut msg.encode().as_slice()
                 )
-                .unwrap());
+                .unwrap();
+                self.abort(reason);
             }
         } else {
             fn emit<E>(&mut self, event: E)
             where
                 E: Topics + scale::Encode,
             {
-                self.events.push(Event::new(event));
+                self.ensure_mutable("emitting events");
+                let event = Event::new(event);
+                self.charge(Operation::Event {
+                    size: event.data.len(),
+                    topics: event.topics.len(),
+                });
+                if self.is_tracing() {
+                    self.trace_step(TraceStep::Event(event.clone()));
+                }
+                self.events.push(event);
             }
 
             fn call<R>(&mut self, _addr: &Address, _data: &[u8]) -> Result<R>
             where
                 R: scale::Decode,
             {
-                unimplemented!();
+                unreachable!("cross-contract calls are performed by `EnvInstance::call_contract`")
             }
 
-            fn finish<V>(&mut self, _: &V)
+            fn finish<V>(&mut self, return_value: &V)
             where
                 V: scale::Encode,
             {
-                unimplemented!();
+                self.current_exec_context_mut().return_data = return_value.encode();
             }
 
             fn revert<V>(&mut self, msg: &V)
@@ -186,10 +1127,11 @@ impl Env for EnvInstance {
                 V: scale::Encode,
             {
                 // Ensure that the type of `V` can only be String.
-                panic!("{}", <String as scale::Decode>::decode(
+                let reason = <String as scale::Decode>::decode(
                     &mut msg.encode().as_slice()
                 )
-                .unwrap());
+                .unwrap();
+                self.abort(reason);
             }
         }
     }
@@ -202,27 +1144,24 @@ impl Env for EnvInstance {
         total: u64,
         description: &[u8],
     ) -> bool {
+        self.ensure_mutable("asset operations");
+        self.charge(Operation::AssetOperation);
         let asset_name = str::from_utf8(asset_name).unwrap();
+        self.record_asset_access(asset_name, None, false);
         if self.assets_info.contains_key(asset_name) {
             return false;
         }
-        self.assets_info.insert(
-            asset_name.to_string(),
+        self.record_asset_access(asset_name, None, true);
+        self.insert_asset(
+            asset_name,
             AssetInfo {
                 issuer: *issuer,
                 fungible,
                 total_supply: total,
-                _description: str::from_utf8(description).unwrap().to_string(),
+                description: str::from_utf8(description).unwrap().to_string(),
                 supplied: 0,
             },
         );
-        if fungible {
-            self.fungible_asset
-                .insert(asset_name.to_string(), HashMap::new());
-        } else {
-            self.not_fungible_asset
-                .insert(asset_name.to_string(), HashMap::new());
-        }
         true
     }
 
@@ -232,7 +1171,10 @@ impl Env for EnvInstance {
         asset_name: &[u8],
         amount: u64,
     ) -> bool {
+        self.ensure_mutable("asset operations");
+        self.charge(Operation::AssetOperation);
         let asset_name = str::from_utf8(asset_name).unwrap();
+        self.record_asset_access(asset_name, None, false);
         if !self.assets_info.contains_key(asset_name) {
             return false;
         }
@@ -244,10 +1186,13 @@ impl Env for EnvInstance {
         if asset_info.issuer != caller {
             return false;
         }
-        if asset_info.total_supply - asset_info.supplied < amount {
-            return false;
+        match asset_info.total_supply.checked_sub(asset_info.supplied) {
+            Some(remaining) if remaining >= amount => (),
+            _ => return false,
         }
         asset_info.supplied += amount;
+        self.record_asset_access(asset_name, None, true);
+        self.record_asset_access(asset_name, Some(to), true);
         let account_balance = self
             .fungible_asset
             .entry(asset_name.to_string())
@@ -264,7 +1209,10 @@ impl Env for EnvInstance {
         asset_name: &[u8],
         uri: &[u8],
     ) -> u64 {
+        self.ensure_mutable("asset operations");
+        self.charge(Operation::AssetOperation);
         let asset_name = str::from_utf8(asset_name).unwrap();
+        self.record_asset_access(asset_name, None, false);
         if !self.assets_info.contains_key(asset_name) {
             return 0;
         }
@@ -280,17 +1228,17 @@ impl Env for EnvInstance {
             return 0;
         }
         asset_info.supplied += 1;
+        let token_id = asset_info.supplied;
+        self.record_asset_access(asset_name, None, true);
+        self.record_asset_access(asset_name, Some(to), true);
         let tokens = self
             .not_fungible_asset
             .entry(asset_name.to_string())
             .or_insert_with(HashMap::new)
             .entry(*to)
             .or_insert_with(HashMap::new);
-        tokens.insert(
-            asset_info.supplied,
-            str::from_utf8(uri).unwrap().to_string(),
-        );
-        asset_info.supplied
+        tokens.insert(token_id, str::from_utf8(uri).unwrap().to_string());
+        token_id
     }
 
     fn transfer_asset(
@@ -300,7 +1248,10 @@ impl Env for EnvInstance {
         amount_or_id: u64,
         from_self: bool,
     ) -> bool {
+        self.ensure_mutable("asset operations");
+        self.charge(Operation::AssetOperation);
         let asset_name = str::from_utf8(asset_name).unwrap();
+        self.record_asset_access(asset_name, None, false);
         if !self.assets_info.contains_key(asset_name) {
             return false;
         }
@@ -311,6 +1262,7 @@ impl Env for EnvInstance {
                 self.get_caller()
             }
         };
+        self.record_asset_access(asset_name, Some(&from), false);
         let asset_info = self.assets_info.get(asset_name).unwrap();
         if asset_info.fungible {
             let amount = amount_or_id;
@@ -322,6 +1274,8 @@ impl Env for EnvInstance {
                 .or_insert(0);
             if *from_balance >= amount {
                 *from_balance -= amount;
+                self.record_asset_access(asset_name, Some(&from), true);
+                self.record_asset_access(asset_name, Some(to), true);
                 let to_balance = self
                     .fungible_asset
                     .get_mut(asset_name)
@@ -344,6 +1298,8 @@ impl Env for EnvInstance {
                 return false;
             }
             let token_uri = from_balance.remove(&token_id).unwrap();
+            self.record_asset_access(asset_name, Some(&from), true);
+            self.record_asset_access(asset_name, Some(to), true);
             let to_balance = self
                 .not_fungible_asset
                 .get_mut(asset_name)
@@ -355,11 +1311,14 @@ impl Env for EnvInstance {
         }
     }
 
-    fn get_asset_balance(&self, to: &Address, asset_name: &[u8]) -> u64 {
+    fn get_asset_balance(&mut self, to: &Address, asset_name: &[u8]) -> u64 {
+        self.charge(Operation::AssetOperation);
         let asset_name = str::from_utf8(asset_name).unwrap();
+        self.record_asset_access(asset_name, None, false);
         if !self.assets_info.contains_key(asset_name) {
             return 0;
         }
+        self.record_asset_access(asset_name, Some(to), false);
         let asset_info = self.assets_info.get(asset_name).unwrap();
         if asset_info.fungible {
             *self
@@ -382,11 +1341,14 @@ impl Env for EnvInstance {
         asset_name: &[u8],
         asset_id: u64,
     ) -> String {
+        self.charge(Operation::AssetOperation);
         let asset_name = str::from_utf8(asset_name).unwrap();
+        self.record_asset_access(asset_name, None, false);
         let ret = String::new();
         if !self.assets_info.contains_key(asset_name) {
             return ret;
         }
+        self.record_asset_access(asset_name, Some(account), false);
         let asset_info = self.assets_info.get_mut(asset_name).unwrap();
         if asset_info.fungible {
             return ret;
@@ -407,11 +1369,14 @@ impl Env for EnvInstance {
         account: &Address,
         asset_name: &[u8],
     ) -> Vec<u64> {
+        self.charge(Operation::AssetOperation);
         let asset_name = str::from_utf8(asset_name).unwrap();
+        self.record_asset_access(asset_name, None, false);
         let mut ret = Vec::new();
         if !self.assets_info.contains_key(asset_name) {
             return ret;
         }
+        self.record_asset_access(asset_name, Some(account), false);
         let asset_info = self.assets_info.get_mut(asset_name).unwrap();
         if asset_info.fungible {
             return ret;
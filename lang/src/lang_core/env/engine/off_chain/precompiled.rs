@@ -0,0 +1,767 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pure-Rust implementations of the precompiled contracts of the blockchain,
+//! which are called like the contracts registered in the off-chain
+//! environment.
+
+use super::{
+    db::{
+        CnsEntry, CompareOp, ConditionData, Fields, KvTableData, NodeType, TableData,
+        TableObject,
+    },
+    Dispatcher, EnvInstance,
+};
+use crate::{
+    lang_core::{
+        env::engine::OnInstance,
+        precompiled::{
+            ADD_OBSERVER_SELECTOR, ADD_SEALER_SELECTOR, CNS_ADDRESS,
+            CNS_GET_ADDRESS_SELECTOR, CNS_INSERT_SELECTOR, CONSENSUS_ADDRESS,
+            CONSENSUS_REMOVE_SELECTOR, CREATE_TABLE_SELECTOR, CRYPTO_ADDRESS,
+            ECRECOVER_ADDRESS, ENTRIES_GET_SELECTOR, ENTRIES_SIZE_SELECTOR,
+            EQ_INT_SELECTOR, EQ_STRING_SELECTOR, GET_ADDRESS_SELECTOR, GET_INT_SELECTOR,
+            GET_SELECTOR, GET_STRING_SELECTOR, GET_UINT_SELECTOR, GE_SELECTOR,
+            GRANT_WRITE_SELECTOR, GT_SELECTOR, INSERT_SELECTOR, KECCAK256_SELECTOR,
+            KV_TABLE_FACTORY_ADDRESS, LE_SELECTOR, LIMIT_SELECTOR, LT_SELECTOR,
+            NEW_CONDITION_SELECTOR, NEW_ENTRY_SELECTOR, NE_INT_SELECTOR,
+            NE_STRING_SELECTOR, OPEN_TABLE_SELECTOR, PERMISSION_ADDRESS,
+            PERMISSION_INSERT_SELECTOR, PERMISSION_REMOVE_SELECTOR, REMOVE_SELECTOR,
+            REVOKE_WRITE_SELECTOR, SELECT_SELECTOR, SET_INT_SELECTOR, SET_SELECTOR,
+            SET_STRING_SELECTOR, SET_UINT_SELECTOR, SET_VALUE_BY_KEY_SELECTOR,
+            SHA256_ADDRESS, SM2_VERIFY_SELECTOR, SM3_SELECTOR, SYSTEM_CONFIG_ADDRESS,
+            TABLE_FACTORY_ADDRESS, TX_COUNT_LIMIT, TX_GAS_LIMIT, UPDATE_SELECTOR,
+        },
+    },
+    DispatchError, DispatchResult,
+};
+use liquid_abi_codec::{Decode, Encode};
+use liquid_primitives::{
+    hash::{keccak256, sm3},
+    types::{address::*, i256, u256, Bytes, Bytes32},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+/// The error code returned by `createTable` if the table already exists.
+const TABLE_ALREADY_EXISTS: i64 = -50001;
+
+/// The error code returned by CNS if the name and version have been
+/// registered.
+const CNS_ALREADY_EXISTS: i64 = -51200;
+
+/// The error code returned by CNS if the version is too long.
+const CNS_VERSION_TOO_LONG: i64 = -51201;
+
+/// The maximum length of versions registered in CNS.
+const CNS_VERSION_MAX_LENGTH: usize = 40;
+
+/// The error code returned by the permission precompiled contract if the
+/// permission has been granted.
+const PERMISSION_ALREADY_GRANTED: i64 = -51000;
+
+/// The error code returned by the permission precompiled contract if the
+/// permission hasn't been granted.
+const PERMISSION_NOT_GRANTED: i64 = -51001;
+
+/// The error code returned by the consensus precompiled contract if the node
+/// ID is invalid.
+const INVALID_NODE_ID: i64 = -51100;
+
+/// The error code returned by the consensus precompiled contract if the last
+/// sealer would be removed or turned into an observer.
+const LAST_SEALER: i64 = -51101;
+
+/// The length of node IDs in hex digits.
+const NODE_ID_LENGTH: usize = 128;
+
+/// The error code returned by the system config precompiled contract if the
+/// key is unsupported or the value is out of range.
+const INVALID_CONFIGURATION_VALUES: i64 = -51300;
+
+/// The minimum value of `tx_gas_limit`.
+const TX_GAS_LIMIT_MIN: i64 = 100_000;
+
+/// Returns the dispatcher of the precompiled contract at `address`, including
+/// the objects returned by precompiled contracts.
+pub fn dispatcher_of(instance: &EnvInstance, address: &Address) -> Option<Dispatcher> {
+    match *address {
+        ECRECOVER_ADDRESS => Some(ecrecover),
+        SHA256_ADDRESS => Some(sha256),
+        CRYPTO_ADDRESS => Some(crypto),
+        CNS_ADDRESS => Some(cns),
+        PERMISSION_ADDRESS => Some(permission),
+        SYSTEM_CONFIG_ADDRESS => Some(system_config),
+        CONSENSUS_ADDRESS => Some(consensus),
+        KV_TABLE_FACTORY_ADDRESS => Some(kv_table_factory),
+        TABLE_FACTORY_ADDRESS => Some(table_factory),
+        _ if instance.tables.is_object(address) => Some(table_object),
+        _ => None,
+    }
+}
+
+fn call_data() -> Vec<u8> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.current_exec_context().call_data.clone()
+    })
+}
+
+/// Splits the call data into the selector and the encoded arguments.
+fn split_call_data(call_data: &[u8]) -> Result<(&[u8], &[u8]), DispatchError> {
+    if call_data.len() < 4 {
+        return Err(DispatchError::CouldNotReadInput);
+    }
+    Ok(call_data.split_at(4))
+}
+
+fn decode_args<T: Decode>(mut input: &[u8]) -> Result<T, DispatchError> {
+    <T as Decode>::decode(&mut input).map_err(|_| DispatchError::InvalidParams)
+}
+
+/// Sets `output` as the raw return data, precompiled contracts don't encode
+/// their output with the codec used by contracts.
+fn finish(output: Vec<u8>) -> DispatchResult {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.current_exec_context_mut().return_data = output;
+    });
+    Ok(())
+}
+
+/// Returns the last 20 bytes of `hash` as an address.
+fn to_address(hash: [u8; 32]) -> Address {
+    let mut address = [0u8; ADDRESS_LENGTH];
+    address.copy_from_slice(&hash[32 - ADDRESS_LENGTH..]);
+    Address::new(address)
+}
+
+/// The output is the signer address left-padded to 32 bytes, or empty if the
+/// signature is invalid.
+fn ecrecover() -> DispatchResult {
+    let mut input = call_data();
+    input.resize(128, 0);
+    let recover = || {
+        if input[32..63].iter().any(|byte| *byte != 0) {
+            return None;
+        }
+        let message = secp256k1::Message::parse_slice(&input[..32]).ok()?;
+        let recovery_id = secp256k1::RecoveryId::parse_rpc(input[63]).ok()?;
+        let signature = secp256k1::Signature::parse_slice(&input[64..]).ok()?;
+        let public_key = secp256k1::recover(&message, &signature, &recovery_id).ok()?;
+        Some(to_address(keccak256(&public_key.serialize()[1..])))
+    };
+
+    match recover() {
+        Some(address) => {
+            let mut output = vec![0u8; 32 - ADDRESS_LENGTH];
+            output.extend_from_slice(&address.0);
+            finish(output)
+        }
+        None => finish(Vec::new()),
+    }
+}
+
+fn sha256() -> DispatchResult {
+    finish(Sha256::digest(&call_data()).to_vec())
+}
+
+/// Verifies an SM2 signature, the signer address is derived from the public
+/// key with SM3.
+fn sm2_verify(hash: &Bytes32, public_key: &[u8], r: &Bytes32, s: &Bytes32) -> bool {
+    use libsm::sm2::signature::{SigCtx, Signature};
+
+    if public_key.len() != 64 {
+        return false;
+    }
+    let ctx = SigCtx::new();
+    let mut encoded = vec![0x04];
+    encoded.extend_from_slice(public_key);
+    match ctx.load_pubkey(&encoded) {
+        Ok(point) => ctx.verify(&hash.0, &point, &Signature::new(&r.0, &s.0)),
+        Err(_) => false,
+    }
+}
+
+fn crypto() -> DispatchResult {
+    let call_data = call_data();
+    let (selector, mut input) = split_call_data(&call_data)?;
+    let output = if selector == KECCAK256_SELECTOR {
+        let data = <Bytes as Decode>::decode(&mut input)
+            .map_err(|_| DispatchError::InvalidParams)?;
+        Bytes32(keccak256(&data)).encode()
+    } else if selector == SM3_SELECTOR {
+        let data = <Bytes as Decode>::decode(&mut input)
+            .map_err(|_| DispatchError::InvalidParams)?;
+        Bytes32(sm3(&data)).encode()
+    } else if selector == SM2_VERIFY_SELECTOR {
+        let (hash, public_key, r, s) =
+            <(Bytes32, Bytes, Bytes32, Bytes32) as Decode>::decode(&mut input)
+                .map_err(|_| DispatchError::InvalidParams)?;
+        if sm2_verify(&hash, &public_key, &r, &s) {
+            (true, to_address(sm3(&public_key))).encode()
+        } else {
+            (false, Address::default()).encode()
+        }
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+    finish(output)
+}
+
+/// Registers `entry` in CNS with `name` and `version`, and returns the
+/// number of registered contracts or an error code.
+pub fn insert_cns(
+    instance: &mut EnvInstance,
+    name: String,
+    version: String,
+    entry: CnsEntry,
+) -> i64 {
+    if version.len() > CNS_VERSION_MAX_LENGTH {
+        return CNS_VERSION_TOO_LONG;
+    }
+    match instance.cns.entry((name, version)) {
+        Entry::Occupied(_) => CNS_ALREADY_EXISTS,
+        Entry::Vacant(vacant) => {
+            vacant.insert(entry);
+            1
+        }
+    }
+}
+
+/// Error codes of CNS are negative, which are returned as `uint256` in two's
+/// complement, i.e., encoded as sign-extended `int256`.
+fn cns() -> DispatchResult {
+    let call_data = call_data();
+    let (selector, input) = split_call_data(&call_data)?;
+    let output = if selector == CNS_INSERT_SELECTOR {
+        let (name, version, address, abi) =
+            decode_args::<(String, String, String, String)>(input)?;
+        let address =
+            Address::from_str(&address).map_err(|_| DispatchError::InvalidParams)?;
+        let code = <EnvInstance as OnInstance>::on_instance(|instance| {
+            instance.ensure_mutable("registering contracts in CNS");
+            insert_cns(instance, name, version, CnsEntry { address, abi })
+        });
+        i256::from(code).encode()
+    } else if selector == CNS_GET_ADDRESS_SELECTOR {
+        let (name, version) = decode_args::<(String, String)>(input)?;
+        <EnvInstance as OnInstance>::on_instance(|instance| {
+            instance
+                .cns
+                .get(&(name, version))
+                .map(|entry| entry.address)
+                .unwrap_or_default()
+        })
+        .encode()
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+    finish(output)
+}
+
+/// Grants or revokes the permission of `account` by updating `accounts`, and
+/// returns the number of affected permissions or an error code.
+fn update_permission(
+    accounts: &mut BTreeSet<Address>,
+    account: Address,
+    grant: bool,
+) -> i64 {
+    let updated = if grant {
+        accounts.insert(account)
+    } else {
+        accounts.remove(&account)
+    };
+    match (updated, grant) {
+        (true, _) => 1,
+        (false, true) => PERMISSION_ALREADY_GRANTED,
+        (false, false) => PERMISSION_NOT_GRANTED,
+    }
+}
+
+fn permission() -> DispatchResult {
+    let call_data = call_data();
+    let (selector, input) = split_call_data(&call_data)?;
+    let grant =
+        selector == PERMISSION_INSERT_SELECTOR || selector == GRANT_WRITE_SELECTOR;
+    let code = if selector == PERMISSION_INSERT_SELECTOR
+        || selector == PERMISSION_REMOVE_SELECTOR
+    {
+        let (table_name, account) = decode_args::<(String, String)>(input)?;
+        let account =
+            Address::from_str(&account).map_err(|_| DispatchError::InvalidParams)?;
+        <EnvInstance as OnInstance>::on_instance(|instance| {
+            instance.ensure_mutable("changing permissions");
+            let permissions = &mut instance.governance.table_permissions;
+            update_permission(permissions.entry(table_name).or_default(), account, grant)
+        })
+    } else if selector == GRANT_WRITE_SELECTOR || selector == REVOKE_WRITE_SELECTOR {
+        let (contract, account) = decode_args::<(Address, Address)>(input)?;
+        <EnvInstance as OnInstance>::on_instance(|instance| {
+            instance.ensure_mutable("changing permissions");
+            let permissions = &mut instance.governance.contract_permissions;
+            update_permission(permissions.entry(contract).or_default(), account, grant)
+        })
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+    finish(i256::from(code).encode())
+}
+
+/// Only `tx_count_limit` and `tx_gas_limit` are supported, whose values must
+/// be integers not less than their minimums.
+fn system_config() -> DispatchResult {
+    let call_data = call_data();
+    let (selector, input) = split_call_data(&call_data)?;
+    if selector != SET_VALUE_BY_KEY_SELECTOR {
+        return Err(DispatchError::UnknownSelector);
+    }
+    let (key, value) = decode_args::<(String, String)>(input)?;
+    let min = match key.as_str() {
+        TX_COUNT_LIMIT => Some(1),
+        TX_GAS_LIMIT => Some(TX_GAS_LIMIT_MIN),
+        _ => None,
+    };
+    let is_valid = match (min, value.parse::<i64>()) {
+        (Some(min), Ok(value)) => value >= min,
+        _ => false,
+    };
+    let code = <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.ensure_mutable("setting system configs");
+        if !is_valid {
+            return INVALID_CONFIGURATION_VALUES;
+        }
+        instance.governance.system_configs.insert(key, value);
+        1
+    });
+    finish(i256::from(code).encode())
+}
+
+/// Sets the type of the node `node_id` in `nodes`, or removes the node if
+/// `node_type` is `None`. Returns the number of affected nodes or an error
+/// code.
+pub fn set_consensus_node(
+    nodes: &mut BTreeMap<String, NodeType>,
+    node_id: String,
+    node_type: Option<NodeType>,
+) -> i64 {
+    if node_id.len() != NODE_ID_LENGTH
+        || !node_id.bytes().all(|byte| byte.is_ascii_hexdigit())
+    {
+        return INVALID_NODE_ID;
+    }
+    let is_sealer = |node_type: Option<&NodeType>| node_type == Some(&NodeType::Sealer);
+    if is_sealer(nodes.get(&node_id))
+        && !is_sealer(node_type.as_ref())
+        && nodes
+            .values()
+            .filter(|node_type| is_sealer(Some(node_type)))
+            .count()
+            == 1
+    {
+        return LAST_SEALER;
+    }
+    match node_type {
+        Some(node_type) => {
+            nodes.insert(node_id, node_type);
+            1
+        }
+        None => nodes.remove(&node_id).map_or(0, |_| 1),
+    }
+}
+
+fn consensus() -> DispatchResult {
+    let call_data = call_data();
+    let (selector, input) = split_call_data(&call_data)?;
+    let node_type = if selector == ADD_SEALER_SELECTOR {
+        Some(NodeType::Sealer)
+    } else if selector == ADD_OBSERVER_SELECTOR {
+        Some(NodeType::Observer)
+    } else if selector == CONSENSUS_REMOVE_SELECTOR {
+        None
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+    let node_id = decode_args::<String>(input)?;
+    let code = <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.ensure_mutable("changing consensus nodes");
+        let nodes = &mut instance.governance.consensus_nodes;
+        set_consensus_node(nodes, node_id, node_type)
+    });
+    finish(i256::from(code).encode())
+}
+
+/// Parses the comma-separated value fields of a table.
+fn parse_fields(fields: &str) -> Vec<String> {
+    fields
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn kv_table_factory() -> DispatchResult {
+    let call_data = call_data();
+    let (selector, input) = split_call_data(&call_data)?;
+    let output = if selector == CREATE_TABLE_SELECTOR {
+        let (name, primary_key, fields) = decode_args::<(String, String, String)>(input)?;
+        <EnvInstance as OnInstance>::on_instance(|instance| {
+            instance.ensure_mutable("creating tables");
+            let kv_tables = &mut instance.tables.kv_tables;
+            if kv_tables.contains_key(&name) {
+                return i256::from(TABLE_ALREADY_EXISTS).encode();
+            }
+            let table = KvTableData {
+                primary_key,
+                fields: parse_fields(&fields),
+                rows: Default::default(),
+            };
+            kv_tables.insert(name, table);
+            i256::from(0).encode()
+        })
+    } else if selector == OPEN_TABLE_SELECTOR {
+        let name = decode_args::<String>(input)?;
+        <EnvInstance as OnInstance>::on_instance(|instance| {
+            let tables = &mut instance.tables;
+            if tables.kv_tables.contains_key(&name) {
+                tables.insert_object(TableObject::KvTable(name)).encode()
+            } else {
+                Address::default().encode()
+            }
+        })
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+    finish(output)
+}
+
+fn table_factory() -> DispatchResult {
+    let call_data = call_data();
+    let (selector, input) = split_call_data(&call_data)?;
+    let output = if selector == CREATE_TABLE_SELECTOR {
+        let (name, key_field, fields) = decode_args::<(String, String, String)>(input)?;
+        <EnvInstance as OnInstance>::on_instance(|instance| {
+            instance.ensure_mutable("creating tables");
+            let tables = &mut instance.tables.tables;
+            if tables.contains_key(&name) {
+                return i256::from(TABLE_ALREADY_EXISTS).encode();
+            }
+            let table = TableData {
+                key_field,
+                fields: parse_fields(&fields),
+                rows: Vec::new(),
+            };
+            tables.insert(name, table);
+            i256::from(0).encode()
+        })
+    } else if selector == OPEN_TABLE_SELECTOR {
+        let name = decode_args::<String>(input)?;
+        <EnvInstance as OnInstance>::on_instance(|instance| {
+            let tables = &mut instance.tables;
+            if tables.tables.contains_key(&name) {
+                tables.insert_object(TableObject::Table(name)).encode()
+            } else {
+                Address::default().encode()
+            }
+        })
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+    finish(output)
+}
+
+/// Dispatches calls to the objects returned by the table precompiled
+/// contracts.
+fn table_object() -> DispatchResult {
+    let call_data = call_data();
+    let (selector, input) = split_call_data(&call_data)?;
+    let (address, object) = <EnvInstance as OnInstance>::on_instance(|instance| {
+        let address = instance.current_address();
+        (address, instance.tables.object(&address).cloned())
+    });
+    let object = object.expect("the object must exist since it's dispatched");
+    let output = <EnvInstance as OnInstance>::on_instance(|instance| match object {
+        TableObject::KvTable(name) => kv_table(instance, &name, selector, input),
+        TableObject::Table(name) => table(instance, &name, selector, input),
+        TableObject::Entry(fields) => entry(instance, &address, fields, selector, input),
+        TableObject::Condition(condition) => {
+            table_condition(instance, &address, condition, selector, input)
+        }
+        TableObject::Entries(rows) => entries(instance, rows, selector, input),
+    })?;
+    finish(output)
+}
+
+/// Returns the fields of the entry at `address`.
+fn entry_fields(
+    instance: &EnvInstance,
+    address: &Address,
+) -> Result<Fields, DispatchError> {
+    match instance.tables.object(address) {
+        Some(TableObject::Entry(fields)) => Ok(fields.clone()),
+        _ => Err(DispatchError::InvalidParams),
+    }
+}
+
+/// Reverts current execution if `fields` contains a field not in the table
+/// named `name`.
+fn check_fields<F>(instance: &mut EnvInstance, name: &str, fields: &Fields, has_field: F)
+where
+    F: Fn(&str) -> bool,
+{
+    if let Some(field) = fields.keys().find(|field| !has_field(field)) {
+        let reason = format!("unknown field `{}` of table `{}`", field, name);
+        instance.abort(reason);
+    }
+}
+
+fn kv_table(
+    instance: &mut EnvInstance,
+    name: &str,
+    selector: &[u8],
+    input: &[u8],
+) -> Result<Vec<u8>, DispatchError> {
+    let output = if selector == GET_SELECTOR {
+        let primary_key = decode_args::<String>(input)?;
+        let row = instance.tables.kv_tables[name]
+            .rows
+            .get(&primary_key)
+            .cloned();
+        match row {
+            Some(fields) => {
+                let address = instance.tables.insert_object(TableObject::Entry(fields));
+                (true, address).encode()
+            }
+            None => (false, Address::default()).encode(),
+        }
+    } else if selector == SET_SELECTOR {
+        let (primary_key, entry) = decode_args::<(String, Address)>(input)?;
+        instance.ensure_mutable("writing tables");
+        let mut fields = entry_fields(instance, &entry)?;
+        let table = instance.tables.kv_tables[name].clone();
+        check_fields(instance, name, &fields, |field| table.has_field(field));
+        fields.insert(table.primary_key, primary_key.clone());
+        let table = instance.tables.kv_tables.get_mut(name).unwrap();
+        table.rows.insert(primary_key, fields);
+        i256::from(1).encode()
+    } else if selector == NEW_ENTRY_SELECTOR {
+        let address = instance
+            .tables
+            .insert_object(TableObject::Entry(Fields::new()));
+        address.encode()
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+    Ok(output)
+}
+
+fn table(
+    instance: &mut EnvInstance,
+    name: &str,
+    selector: &[u8],
+    input: &[u8],
+) -> Result<Vec<u8>, DispatchError> {
+    let condition_of =
+        |instance: &EnvInstance, address: &Address| match instance.tables.object(address)
+        {
+            Some(TableObject::Condition(condition)) => Ok(condition.clone()),
+            _ => Err(DispatchError::InvalidParams),
+        };
+
+    let table = instance.tables.tables[name].clone();
+    let output = if selector == SELECT_SELECTOR {
+        let (key, condition) = decode_args::<(String, Address)>(input)?;
+        let condition = condition_of(instance, &condition)?;
+        let rows = condition
+            .filter(&table, &key)
+            .into_iter()
+            .map(|index| table.rows[index].clone())
+            .collect();
+        instance
+            .tables
+            .insert_object(TableObject::Entries(rows))
+            .encode()
+    } else if selector == INSERT_SELECTOR {
+        let (key, entry) = decode_args::<(String, Address)>(input)?;
+        instance.ensure_mutable("writing tables");
+        let mut fields = entry_fields(instance, &entry)?;
+        check_fields(instance, name, &fields, |field| table.has_field(field));
+        fields.insert(table.key_field, key);
+        let table = instance.tables.tables.get_mut(name).unwrap();
+        table.rows.push(fields);
+        i256::from(1).encode()
+    } else if selector == UPDATE_SELECTOR {
+        let (key, entry, condition) = decode_args::<(String, Address, Address)>(input)?;
+        instance.ensure_mutable("writing tables");
+        let mut fields = entry_fields(instance, &entry)?;
+        let condition = condition_of(instance, &condition)?;
+        check_fields(instance, name, &fields, |field| table.has_field(field));
+        // The key of an entry can't be changed by updating.
+        fields.remove(&table.key_field);
+        let indices = condition.filter(&table, &key);
+        let rows = &mut instance.tables.tables.get_mut(name).unwrap().rows;
+        for index in &indices {
+            rows[*index].extend(fields.clone());
+        }
+        i256::from(indices.len()).encode()
+    } else if selector == REMOVE_SELECTOR {
+        let (key, condition) = decode_args::<(String, Address)>(input)?;
+        instance.ensure_mutable("writing tables");
+        let condition = condition_of(instance, &condition)?;
+        let indices = condition.filter(&table, &key);
+        let rows = &mut instance.tables.tables.get_mut(name).unwrap().rows;
+        for index in indices.iter().rev() {
+            rows.remove(*index);
+        }
+        i256::from(indices.len()).encode()
+    } else if selector == NEW_ENTRY_SELECTOR {
+        instance
+            .tables
+            .insert_object(TableObject::Entry(Fields::new()))
+            .encode()
+    } else if selector == NEW_CONDITION_SELECTOR {
+        instance
+            .tables
+            .insert_object(TableObject::Condition(ConditionData::default()))
+            .encode()
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+    Ok(output)
+}
+
+/// Values are stored as strings, a value which can't be parsed is read as
+/// the default value of the type.
+fn entry(
+    instance: &mut EnvInstance,
+    address: &Address,
+    mut fields: Fields,
+    selector: &[u8],
+    input: &[u8],
+) -> Result<Vec<u8>, DispatchError> {
+    fn read<T: FromStr>(fields: &Fields, field: &str) -> Option<T> {
+        fields.get(field).and_then(|value| value.parse().ok())
+    }
+
+    // `set(string,address)` of entries shares its selector with `set` of KV
+    // tables, so `SET_SELECTOR` is used here.
+    let output = if selector == GET_INT_SELECTOR {
+        read(&fields, &decode_args::<String>(input)?)
+            .unwrap_or_else(|| i256::from(0))
+            .encode()
+    } else if selector == GET_UINT_SELECTOR {
+        read(&fields, &decode_args::<String>(input)?)
+            .unwrap_or_else(|| u256::from(0u8))
+            .encode()
+    } else if selector == GET_ADDRESS_SELECTOR {
+        read::<Address>(&fields, &decode_args::<String>(input)?)
+            .unwrap_or_default()
+            .encode()
+    } else if selector == GET_STRING_SELECTOR {
+        read::<String>(&fields, &decode_args::<String>(input)?)
+            .unwrap_or_default()
+            .encode()
+    } else {
+        let (field, value) = if selector == SET_INT_SELECTOR {
+            let (field, value) = decode_args::<(String, i256)>(input)?;
+            (field, value.to_string())
+        } else if selector == SET_UINT_SELECTOR {
+            let (field, value) = decode_args::<(String, u256)>(input)?;
+            (field, value.to_string())
+        } else if selector == SET_SELECTOR {
+            let (field, value) = decode_args::<(String, Address)>(input)?;
+            (field, value.to_string())
+        } else if selector == SET_STRING_SELECTOR {
+            decode_args::<(String, String)>(input)?
+        } else {
+            return Err(DispatchError::UnknownSelector);
+        };
+        fields.insert(field, value);
+        *instance.tables.object_mut(address).unwrap() = TableObject::Entry(fields);
+        Vec::new()
+    };
+    Ok(output)
+}
+
+fn table_condition(
+    instance: &mut EnvInstance,
+    address: &Address,
+    mut condition: ConditionData,
+    selector: &[u8],
+    input: &[u8],
+) -> Result<Vec<u8>, DispatchError> {
+    let op = if selector == EQ_INT_SELECTOR || selector == EQ_STRING_SELECTOR {
+        CompareOp::Eq
+    } else if selector == NE_INT_SELECTOR || selector == NE_STRING_SELECTOR {
+        CompareOp::Ne
+    } else if selector == GT_SELECTOR {
+        CompareOp::Gt
+    } else if selector == GE_SELECTOR {
+        CompareOp::Ge
+    } else if selector == LT_SELECTOR {
+        CompareOp::Lt
+    } else if selector == LE_SELECTOR {
+        CompareOp::Le
+    } else if selector == LIMIT_SELECTOR {
+        let (offset, count) = decode_args::<(i256, i256)>(input)?;
+        let to_usize = |value: i256| {
+            value
+                .to_string()
+                .parse::<usize>()
+                .map_err(|_| DispatchError::InvalidParams)
+        };
+        condition.limit = Some((to_usize(offset)?, to_usize(count)?));
+        *instance.tables.object_mut(address).unwrap() = TableObject::Condition(condition);
+        return Ok(Vec::new());
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+
+    let (field, value) =
+        if selector == EQ_STRING_SELECTOR || selector == NE_STRING_SELECTOR {
+            decode_args::<(String, String)>(input)?
+        } else {
+            let (field, value) = decode_args::<(String, i256)>(input)?;
+            (field, value.to_string())
+        };
+    condition.comparisons.push((op, field, value));
+    *instance.tables.object_mut(address).unwrap() = TableObject::Condition(condition);
+    Ok(Vec::new())
+}
+
+fn entries(
+    instance: &mut EnvInstance,
+    rows: Vec<Fields>,
+    selector: &[u8],
+    input: &[u8],
+) -> Result<Vec<u8>, DispatchError> {
+    let output = if selector == ENTRIES_GET_SELECTOR {
+        let index = decode_args::<i256>(input)?;
+        let fields = index
+            .to_string()
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| rows.get(index))
+            .ok_or(DispatchError::InvalidParams)?;
+        instance
+            .tables
+            .insert_object(TableObject::Entry(fields.clone()))
+            .encode()
+    } else if selector == ENTRIES_SIZE_SELECTOR {
+        i256::from(rows.len()).encode()
+    } else {
+        return Err(DispatchError::UnknownSelector);
+    };
+    Ok(output)
+}
@@ -0,0 +1,472 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The JSON representation of the off-chain environment.
+//!
+//! Bytes, addresses and hashes are represented as hex strings prefixed with
+//! `0x`. Storage keys following the `field$index` layout are represented as
+//! `field` or `field$0x<index>`, other storage keys are represented as hex
+//! strings, which can be told apart since field names never start with `0x`.
+
+use super::{
+    db::{
+        CnsEntry, Governance, KvTableData, NodeType, TableData, TableObject, TableStore,
+    },
+    AssetInfo, Block, ContractStorage, EnvInstance, Event,
+};
+use liquid_primitives::types::{address::ADDRESS_LENGTH, hash::HASH_LENGTH, Address};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[derive(Serialize, Deserialize)]
+pub struct State {
+    /// The storage entries of contracts, indexed by contract addresses.
+    contracts: BTreeMap<String, BTreeMap<String, String>>,
+    /// The code of contract accounts, indexed by contract addresses.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    codes: BTreeMap<String, String>,
+    blocks: Vec<BlockState>,
+    events: Vec<EventState>,
+    assets: BTreeMap<String, AssetState>,
+    #[serde(default)]
+    tables: TablesState,
+    /// The contracts registered in CNS.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    cns: Vec<CnsState>,
+    /// The state of the permission, system config and consensus precompiled
+    /// contracts, which is the one of a newly built chain if absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    governance: Option<GovernanceState>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlockState {
+    number: u64,
+    timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EventState {
+    topics: Vec<String>,
+    data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AssetState {
+    issuer: String,
+    fungible: bool,
+    total_supply: u64,
+    description: String,
+    supplied: u64,
+    /// The balances of accounts, only for fungible asset.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    balances: BTreeMap<String, u64>,
+    /// The tokens owned by accounts, only for not fungible asset.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tokens: BTreeMap<String, BTreeMap<u64, String>>,
+}
+
+/// The state of the table precompiled contracts.
+#[derive(Default, Serialize, Deserialize)]
+struct TablesState {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    kv_tables: BTreeMap<String, KvTableData>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tables: BTreeMap<String, TableData>,
+    /// The objects opened by contracts, indexed by their addresses.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    objects: BTreeMap<String, TableObject>,
+    /// The serial number of the next object.
+    #[serde(default)]
+    next_object: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CnsState {
+    name: String,
+    version: String,
+    address: String,
+    abi: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GovernanceState {
+    /// The accounts allowed to write each table, indexed by table names.
+    table_permissions: BTreeMap<String, BTreeSet<String>>,
+    /// The accounts allowed to write each contract, indexed by contract
+    /// addresses.
+    contract_permissions: BTreeMap<String, BTreeSet<String>>,
+    system_configs: BTreeMap<String, String>,
+    consensus_nodes: BTreeMap<String, NodeType>,
+}
+
+type Result<T> = core::result::Result<T, String>;
+
+fn encode_hex(bytes: &[u8]) -> String {
+    let mut ret = String::with_capacity(bytes.len() * 2 + 2);
+    ret.push_str("0x");
+    for byte in bytes {
+        ret.push_str(&format!("{:02x}", byte));
+    }
+    ret
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>> {
+    let digits = s
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("`{}` is not prefixed with `0x`", s))?;
+    if digits.len() % 2 != 0 || !digits.is_ascii() {
+        return Err(format!("`{}` is not a valid hex string", s));
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| format!("`{}` is not a valid hex string", s))
+        })
+        .collect()
+}
+
+fn decode_sized(s: &str, len: usize) -> Result<Vec<u8>> {
+    let bytes = decode_hex(s)?;
+    if bytes.len() != len {
+        return Err(format!("`{}` is expected to be of {} bytes", s, len));
+    }
+    Ok(bytes)
+}
+
+fn decode_address(s: &str) -> Result<Address> {
+    let mut address = [0u8; ADDRESS_LENGTH];
+    address.copy_from_slice(&decode_sized(s, ADDRESS_LENGTH)?);
+    Ok(Address::new(address))
+}
+
+fn decode_accounts(accounts: &BTreeSet<String>) -> Result<BTreeSet<Address>> {
+    accounts
+        .iter()
+        .map(|account| decode_address(account))
+        .collect()
+}
+
+fn encode_accounts(accounts: &BTreeSet<Address>) -> BTreeSet<String> {
+    accounts.iter().map(ToString::to_string).collect()
+}
+
+fn encode_storage_key(key: &[u8]) -> String {
+    let (field, index) = match key.iter().position(|byte| *byte == b'$') {
+        Some(pos) => (&key[..pos], Some(&key[pos + 1..])),
+        None => (key, None),
+    };
+    match core::str::from_utf8(field) {
+        Ok(field) if !field.is_empty() && !field.starts_with("0x") => match index {
+            Some(index) => format!("{}${}", field, encode_hex(index)),
+            None => field.to_owned(),
+        },
+        _ => encode_hex(key),
+    }
+}
+
+fn decode_storage_key(s: &str) -> Result<Vec<u8>> {
+    if s.starts_with("0x") {
+        return decode_hex(s);
+    }
+
+    match s.find('$') {
+        Some(pos) => {
+            let mut key = s[..=pos].as_bytes().to_vec();
+            key.extend(decode_hex(&s[pos + 1..])?);
+            Ok(key)
+        }
+        None => Ok(s.as_bytes().to_vec()),
+    }
+}
+
+impl EnvInstance {
+    pub(super) fn dump_state(&self) -> State {
+        let contracts = self
+            .contract_storages
+            .iter()
+            .filter(|(_, storage)| !storage.entries().is_empty())
+            .map(|(address, storage)| {
+                let entries = storage
+                    .entries()
+                    .iter()
+                    .map(|(key, value)| (encode_storage_key(key), encode_hex(value)))
+                    .collect();
+                (address.to_string(), entries)
+            })
+            .collect();
+
+        let codes = self
+            .codes
+            .iter()
+            .map(|(address, code)| (address.to_string(), encode_hex(code)))
+            .collect();
+
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| BlockState {
+                number: block.block_number(),
+                timestamp: block.timestamp(),
+            })
+            .collect();
+
+        let events = self
+            .events
+            .iter()
+            .map(|event| EventState {
+                topics: event.topics.iter().map(ToString::to_string).collect(),
+                data: encode_hex(&event.data),
+            })
+            .collect();
+
+        let assets = self
+            .assets_info
+            .iter()
+            .map(|(name, info)| {
+                let balances = self
+                    .fungible_asset
+                    .get(name)
+                    .map(|balances| {
+                        balances
+                            .iter()
+                            .map(|(account, balance)| (account.to_string(), *balance))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let tokens = self
+                    .not_fungible_asset
+                    .get(name)
+                    .map(|ledger| {
+                        ledger
+                            .iter()
+                            .filter(|(_, tokens)| !tokens.is_empty())
+                            .map(|(account, tokens)| {
+                                (
+                                    account.to_string(),
+                                    tokens.clone().into_iter().collect(),
+                                )
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let asset = AssetState {
+                    issuer: info.issuer.to_string(),
+                    fungible: info.fungible,
+                    total_supply: info.total_supply,
+                    description: info.description.clone(),
+                    supplied: info.supplied,
+                    balances,
+                    tokens,
+                };
+                (name.clone(), asset)
+            })
+            .collect();
+
+        let (objects, next_object) = self.tables.objects();
+        let tables = TablesState {
+            kv_tables: self.tables.kv_tables.clone(),
+            tables: self.tables.tables.clone(),
+            objects: objects
+                .iter()
+                .map(|(address, object)| (address.to_string(), object.clone()))
+                .collect(),
+            next_object,
+        };
+
+        let cns = self
+            .cns
+            .iter()
+            .map(|((name, version), entry)| CnsState {
+                name: name.clone(),
+                version: version.clone(),
+                address: entry.address.to_string(),
+                abi: entry.abi.clone(),
+            })
+            .collect();
+
+        let governance = GovernanceState {
+            table_permissions: self
+                .governance
+                .table_permissions
+                .iter()
+                .map(|(table, accounts)| (table.clone(), encode_accounts(accounts)))
+                .collect(),
+            contract_permissions: self
+                .governance
+                .contract_permissions
+                .iter()
+                .map(|(contract, accounts)| {
+                    (contract.to_string(), encode_accounts(accounts))
+                })
+                .collect(),
+            system_configs: self.governance.system_configs.clone(),
+            consensus_nodes: self.governance.consensus_nodes.clone(),
+        };
+
+        State {
+            contracts,
+            codes,
+            blocks,
+            events,
+            assets,
+            tables,
+            cns,
+            governance: Some(governance),
+        }
+    }
+
+    /// Replaces contract storage and code, blocks, events, assets, tables,
+    /// CNS and governance state with the ones in `state`. Nothing is changed
+    /// if `state` is invalid.
+    pub(super) fn load_state(&mut self, state: State) -> Result<()> {
+        let mut contract_storages = HashMap::new();
+        for (address, entries) in state.contracts {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| Ok((decode_storage_key(key)?, decode_hex(value)?)))
+                .collect::<Result<_>>()?;
+            contract_storages.insert(
+                decode_address(&address)?,
+                ContractStorage::from_entries(entries),
+            );
+        }
+
+        let codes = state
+            .codes
+            .iter()
+            .map(|(address, code)| Ok((decode_address(address)?, decode_hex(code)?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        if state.blocks.is_empty() {
+            return Err(String::from("there must be at least one block"));
+        }
+        let blocks = state
+            .blocks
+            .iter()
+            .map(|block| Block::new(block.number, block.timestamp))
+            .collect();
+
+        let events = state
+            .events
+            .iter()
+            .map(|event| {
+                let topics = event
+                    .topics
+                    .iter()
+                    .map(|topic| decode_sized(topic, HASH_LENGTH).map(Into::into))
+                    .collect::<Result<_>>()?;
+                Ok(Event {
+                    data: decode_hex(&event.data)?,
+                    topics,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let mut assets_info = HashMap::new();
+        let mut fungible_asset = HashMap::new();
+        let mut not_fungible_asset = HashMap::new();
+        for (name, asset) in state.assets {
+            if asset.fungible {
+                let balances = asset
+                    .balances
+                    .iter()
+                    .map(|(account, balance)| Ok((decode_address(account)?, *balance)))
+                    .collect::<Result<_>>()?;
+                fungible_asset.insert(name.clone(), balances);
+            } else {
+                let ledger = asset
+                    .tokens
+                    .iter()
+                    .map(|(account, tokens)| {
+                        Ok((
+                            decode_address(account)?,
+                            tokens.clone().into_iter().collect(),
+                        ))
+                    })
+                    .collect::<Result<_>>()?;
+                not_fungible_asset.insert(name.clone(), ledger);
+            }
+
+            let info = AssetInfo {
+                issuer: decode_address(&asset.issuer)?,
+                fungible: asset.fungible,
+                total_supply: asset.total_supply,
+                description: asset.description,
+                supplied: asset.supplied,
+            };
+            assets_info.insert(name, info);
+        }
+
+        let mut tables = TableStore::default();
+        tables.kv_tables = state.tables.kv_tables;
+        tables.tables = state.tables.tables;
+        let objects = state
+            .tables
+            .objects
+            .into_iter()
+            .map(|(address, object)| Ok((decode_address(&address)?, object)))
+            .collect::<Result<_>>()?;
+        tables.set_objects(objects, state.tables.next_object);
+
+        let cns = state
+            .cns
+            .into_iter()
+            .map(|entry| {
+                let address = decode_address(&entry.address)?;
+                let cns_entry = CnsEntry {
+                    address,
+                    abi: entry.abi,
+                };
+                Ok(((entry.name, entry.version), cns_entry))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        let governance = match state.governance {
+            Some(governance) => Governance {
+                table_permissions: governance
+                    .table_permissions
+                    .iter()
+                    .map(|(table, accounts)| {
+                        Ok((table.clone(), decode_accounts(accounts)?))
+                    })
+                    .collect::<Result<_>>()?,
+                contract_permissions: governance
+                    .contract_permissions
+                    .iter()
+                    .map(|(contract, accounts)| {
+                        Ok((decode_address(contract)?, decode_accounts(accounts)?))
+                    })
+                    .collect::<Result<_>>()?,
+                system_configs: governance.system_configs,
+                consensus_nodes: governance.consensus_nodes,
+            },
+            None => Governance::default(),
+        };
+
+        self.contract_storages = contract_storages;
+        self.codes = codes.into();
+        self.blocks = blocks;
+        self.next_block_number = None;
+        self.next_block_timestamp = None;
+        self.events = events;
+        self.assets_info = assets_info.into();
+        self.fungible_asset = fungible_asset.into();
+        self.not_fungible_asset = not_fungible_asset.into();
+        self.tables = tables.into();
+        self.cns = cns.into();
+        self.governance = governance.into();
+        Ok(())
+    }
+}
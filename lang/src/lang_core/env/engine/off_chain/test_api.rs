@@ -10,9 +10,69 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{EnvInstance, Event, ExecContext};
-use crate::lang_core::env::engine::OnInstance;
-use liquid_primitives::types::address::*;
+use super::{
+    precompiled, Block, CodeEntry, EnvInstance, Event, ExecContext, DEFAULT_CONTRACT_CODE,
+};
+use crate::{
+    lang_core::{env::engine::OnInstance, precompiled::SYS_TABLES},
+    DispatchError, You_Should_Use_An_Valid_Event_Topic_Type,
+};
+use cfg_if::cfg_if;
+use core::marker::PhantomData;
+use liquid_primitives::{
+    types::{address::*, Hash},
+    EventSignature, Selector, Topics,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+};
+
+cfg_if! {
+    if #[cfg(feature = "solidity-compatible")] {
+        use liquid_abi_codec::Encode;
+    } else {
+        use scale::Encode;
+    }
+}
+
+pub use super::{
+    accounts::{Account, AccountFactory, Signature},
+    db::{
+        serializing_keys, AccessSet, CallTrace, CnsEntry, Cost, CostTable, EntryChange,
+        ExecTrace, FieldDiff, Fields, KvTableData, NodeType, StateKey, StorageDiff,
+        TableData, TraceStep,
+    },
+    AssetInfo, Dispatcher,
+};
+
+/// Resets the off-chain environment to its initial state, discarding all
+/// contract storage, registered contracts, events, assets, blocks, execution
+/// contexts and snapshots.
+pub fn reset() {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        *instance = EnvInstance::default();
+    });
+    crate::storage::reset_mutable_call_flag();
+}
+
+/// Executes `f` in a fresh off-chain environment, and restores the previous
+/// environment afterwards, even if `f` panics.
+pub fn run_in_isolated_env<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let previous =
+        <EnvInstance as OnInstance>::on_instance(|instance| core::mem::take(instance));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    <EnvInstance as OnInstance>::on_instance(|instance| *instance = previous);
+    match result {
+        Ok(ret) => ret,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
 
 /// Pushes a contract execution context.
 ///
@@ -58,6 +118,385 @@ pub fn pop_execution_context() {
     })
 }
 
+/// Registers a contract at `address`, then calls to `address` via
+/// `env::call` will be dispatched by `dispatcher` against the isolated storage
+/// of the contract.
+///
+/// If `address` has no code yet, a simulated code will be set for it, so that
+/// it's treated as a contract account.
+pub fn register_contract(address: Address, dispatcher: Dispatcher) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.contracts.insert(address, dispatcher);
+        instance
+            .codes
+            .entry(address)
+            .or_insert_with(|| DEFAULT_CONTRACT_CODE.to_vec());
+    })
+}
+
+/// Registers a contract type with `code`, so that contracts can create
+/// instances of it by calling `liquid_lang::env::create` with the same code.
+///
+/// The `constructor` reads the encoded arguments via `get_call_data` in
+/// `CallMode::Deploy`, and the created contracts are registered with
+/// `dispatcher` and `method_names`.
+pub fn register_code(
+    code: Vec<u8>,
+    constructor: Dispatcher,
+    dispatcher: Dispatcher,
+    method_names: &'static [(Selector, &'static str)],
+) {
+    assert!(!code.is_empty(), "the code of a contract must not be empty");
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.code_entries.insert(
+            code,
+            CodeEntry {
+                constructor,
+                dispatcher,
+                method_names,
+            },
+        );
+    })
+}
+
+/// Registers the names of the methods of the contract at `address`, so that
+/// they can be shown in execution traces.
+pub fn register_method_names(address: Address, names: &[(Selector, &str)]) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let method_names = instance.method_names.entry(address).or_default();
+        for (selector, name) in names {
+            method_names.insert(*selector, (*name).to_owned());
+        }
+    })
+}
+
+/// Registers the names of the methods of the contract being executed in
+/// current execution context(or the zero address if there is none).
+pub fn register_current_method_names(names: &[(Selector, &str)]) {
+    let address =
+        <EnvInstance as OnInstance>::on_instance(|instance| instance.current_address());
+    register_method_names(address, names);
+}
+
+/// Returns whether a contract had been registered at `address`.
+pub fn is_contract_registered(address: &Address) -> bool {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.contracts.contains_key(address)
+    })
+}
+
+/// The kind of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountKind {
+    ExternallyOwned,
+    Contract {
+        /// The code of the contract.
+        code: Vec<u8>,
+    },
+}
+
+/// Declares `address` as a contract account with `code`, so that the code size
+/// of it observed by contracts is the length of `code`.
+///
+/// # Panics
+///
+/// If `code` is empty, since accounts without code are externally owned.
+pub fn set_contract_code(address: Address, code: Vec<u8>) {
+    assert!(!code.is_empty(), "the code of a contract must not be empty");
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.codes.insert(address, code);
+    })
+}
+
+/// Declares `address` as a contract account with a simulated code of
+/// `code_size` bytes.
+///
+/// # Panics
+///
+/// If `code_size` is 0.
+pub fn set_contract_code_size(address: Address, code_size: usize) {
+    set_contract_code(address, vec![0x00; code_size]);
+}
+
+/// Declares `address` as an externally owned account by removing its code.
+/// If a contract has been registered at `address`, it's unregistered as well.
+pub fn set_externally_owned(address: Address) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.codes.remove(&address);
+        instance.contracts.remove(&address);
+    })
+}
+
+/// Returns the kind of the account at `address`.
+pub fn get_account_kind(address: &Address) -> AccountKind {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        match instance.codes.get(address) {
+            Some(code) => AccountKind::Contract { code: code.clone() },
+            None => AccountKind::ExternallyOwned,
+        }
+    })
+}
+
+/// Deploys a contract at `address`.
+///
+/// The `constructor` is executed in a new execution context whose callee is
+/// `address`, and the caller of it is the caller of current execution
+/// context(or the zero address if there is none). The same as calls, the new
+/// execution context is one level deeper than current one. After that, the
+/// contract is registered with `dispatcher`.
+pub fn deploy_contract<F>(address: Address, dispatcher: Dispatcher, constructor: F)
+where
+    F: FnOnce(),
+{
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let caller = instance
+            .exec_contexts
+            .last()
+            .map(|exec_context| exec_context.caller())
+            .unwrap_or_default();
+        instance.enter_call_trace(caller, address, None);
+        let mut exec_context = ExecContext::new(caller, address);
+        exec_context.depth = instance.current_depth() + 1;
+        instance.exec_contexts.push(exec_context);
+    });
+    constructor();
+    pop_execution_context();
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.leave_call_trace(false);
+    });
+    register_contract(address, dispatcher);
+}
+
+/// Calls `dispatcher` with `data` as call data in a new execution context, and
+/// returns the output of it.
+///
+/// The new execution context has the same caller, callee, call depth and
+/// static mode as current one, so the dispatcher operates on the storage of
+/// the contract being tested.
+pub fn dispatch_call(
+    dispatcher: Dispatcher,
+    data: &[u8],
+) -> Result<Vec<u8>, DispatchError> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let caller = instance
+            .exec_contexts
+            .last()
+            .map(|exec_context| exec_context.caller())
+            .unwrap_or_default();
+        let callee = instance.current_address();
+        instance.enter_call_trace(caller, callee, Some(data));
+        let mut exec_context = ExecContext::new(caller, callee);
+        exec_context.call_data = data.to_vec();
+        exec_context.depth = instance.current_depth();
+        exec_context.is_static = instance.is_static();
+        instance.exec_contexts.push(exec_context);
+    });
+    let result = dispatcher();
+    let exec_context = <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.leave_call_trace(result.is_err());
+        instance
+            .exec_contexts
+            .pop()
+            .expect("there must be at least one execution context in test environment")
+    });
+    result.map(|_| exec_context.return_data)
+}
+
+/// The information of a reverted execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revert {
+    /// The decoded revert reason.
+    pub reason: String,
+}
+
+/// Executes `f` as a transaction, the same as the off-chain environment does
+/// for every cross-contract call.
+///
+/// If `f` reverts, all changes made by it to contract storage, events and
+/// assets will be rolled back, and the revert reason will be returned.
+/// Panics not caused by revert, e.g., failed assertions, are not caught.
+///
+/// # Note
+///
+/// The cached states held by contract objects are not rolled back, because
+/// they live outside the off-chain environment.
+pub fn transact<F, R>(f: F) -> Result<R, Revert>
+where
+    F: FnOnce() -> R,
+{
+    EnvInstance::transact(f).map_err(|reason| Revert { reason })
+}
+
+/// Takes a snapshot of the whole off-chain environment, including contract
+/// storage, events, assets, execution contexts, blocks and debug messages, and
+/// names it as `name`.
+///
+/// # Note
+///
+/// The snapshot taken previously with the same name will be replaced.
+pub fn take_snapshot(name: &str) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let snapshot = instance.take_snapshot();
+        instance.snapshots.insert(name.to_owned(), snapshot);
+    })
+}
+
+/// Restores the off-chain environment to the snapshot named `name`. The
+/// snapshot is kept after restoring, so it can be restored repeatedly.
+///
+/// # Panics
+///
+/// If there is no snapshot named `name`.
+pub fn restore_snapshot(name: &str) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let snapshot = instance.named_snapshot(name).clone();
+        instance.restore_snapshot(snapshot);
+    })
+}
+
+/// Returns the changes of contract storage since the snapshot named `name`
+/// was taken.
+///
+/// # Panics
+///
+/// If there is no snapshot named `name`.
+pub fn diff_snapshot(name: &str) -> StorageDiff {
+    <EnvInstance as OnInstance>::on_instance(|instance| instance.diff_since(name))
+}
+
+/// Executes `f` and returns the contract storage and asset ledgers read and
+/// written by it, which can be used to check whether two executions can be
+/// executed in parallel.
+///
+/// # Note
+///
+/// Contract objects cache the state they have accessed, so the accesses to
+/// cached state are invisible to the off-chain environment. Call methods via
+/// `TestableStorage::call` to observe all accesses of them.
+pub fn trace_access<F, R>(f: F) -> (R, AccessSet)
+where
+    F: FnOnce() -> R,
+{
+    EnvInstance::trace_access(f)
+}
+
+/// Executes `f` and returns the trace of it, which consists of nested calls,
+/// storage operations, events and the revert point. The trace can be printed
+/// as an indented tree, e.g., `println!("{}", trace)`.
+///
+/// # Note
+///
+/// If `f` panics, the trace recorded so far is discarded and the panic is
+/// propagated. As with `trace_access`, call methods via
+/// `TestableStorage::call` to observe the storage operations of them.
+pub fn trace_execution<F, R>(f: F) -> (R, ExecTrace)
+where
+    F: FnOnce() -> R,
+{
+    EnvInstance::trace_execution(f)
+}
+
+/// Sets the prices of operations used to calculate cost.
+pub fn set_cost_table(cost_table: CostTable) {
+    <EnvInstance as OnInstance>::on_instance(|instance| instance.cost_table = cost_table)
+}
+
+/// Executes `f` and returns the cost of it, which includes storage accesses,
+/// emitted events, cross-contract calls and asset operations.
+///
+/// # Note
+///
+/// Contract objects cache the state they have accessed and only write it back
+/// when flushed, call methods via `TestableStorage::call` to get the real cost
+/// of them.
+pub fn measure_cost<F, R>(f: F) -> (R, Cost)
+where
+    F: FnOnce() -> R,
+{
+    EnvInstance::metered(None, f)
+}
+
+/// Executes `f` and asserts its cost doesn't exceed `budget`.
+///
+/// # Panics
+///
+/// Once the cost of `f` exceeds `budget`.
+pub fn with_budget<F, R>(budget: u64, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    EnvInstance::metered(Some(budget), f).0
+}
+
+/// Resets the simulated chain to contain only a genesis block stamped with
+/// `timestamp`, so that the time observed by contracts is deterministic.
+pub fn set_genesis_timestamp(timestamp: u64) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.blocks = vec![Block::new(0, timestamp)];
+        instance.next_block_number = None;
+        instance.next_block_timestamp = None;
+    })
+}
+
+/// Resets the simulated chain to contain only a genesis block stamped with
+/// current time, for tests which depend on the wall-clock time. By default,
+/// the genesis block is stamped with 0.
+pub fn use_wall_clock() {
+    use chrono::prelude::Utc;
+
+    set_genesis_timestamp(Utc::now().timestamp() as u64)
+}
+
+/// Produces a new block on top of current block.
+///
+/// By default, the number of the new block is the one of current block plus 1,
+/// and its timestamp is later than current block by 1 second, unless they are
+/// specified via [`set_next_block_number`] or [`set_next_block_timestamp`].
+pub fn advance_block() {
+    advance_blocks(1);
+}
+
+/// Produces `n` blocks on top of current block.
+///
+/// # Note
+///
+/// Only the last produced block is recorded, and the number or timestamp
+/// specified via [`set_next_block_number`] or [`set_next_block_timestamp`] is
+/// applied to it.
+pub fn advance_blocks(n: u64) {
+    <EnvInstance as OnInstance>::on_instance(|instance| instance.advance_blocks(n))
+}
+
+/// Specifies the number of the next produced block.
+///
+/// # Panics
+///
+/// If `block_number` is not greater than the number of current block.
+pub fn set_next_block_number(block_number: u64) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        assert!(
+            block_number > instance.current_block().block_number(),
+            "the number of next block must be greater than current one"
+        );
+        instance.next_block_number = Some(block_number);
+    })
+}
+
+/// Specifies the timestamp of the next produced block.
+///
+/// # Panics
+///
+/// If `timestamp` is earlier than the timestamp of current block.
+pub fn set_next_block_timestamp(timestamp: u64) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        assert!(
+            timestamp >= instance.current_block().timestamp(),
+            "the timestamp of next block must not be earlier than current one"
+        );
+        instance.next_block_timestamp = Some(timestamp);
+    })
+}
+
 /// The default accounts.
 pub struct DefaultAccounts {
     pub alice: Address,
@@ -82,9 +521,478 @@ pub fn default_accounts() -> DefaultAccounts {
     }
 }
 
+/// Registers an asset directly, without going through a contract.
+///
+/// # Panics
+///
+/// If there is already an asset named `name`.
+pub fn register_asset(
+    name: &str,
+    issuer: Address,
+    fungible: bool,
+    total_supply: u64,
+    description: &str,
+) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        assert!(
+            !instance.assets_info.contains_key(name),
+            "the asset `{}` has been registered",
+            name
+        );
+        instance.insert_asset(
+            name,
+            AssetInfo {
+                issuer,
+                fungible,
+                total_supply,
+                description: description.to_owned(),
+                supplied: 0,
+            },
+        );
+    })
+}
+
+/// Returns the information of the asset named `name`, if registered.
+pub fn get_asset_info(name: &str) -> Option<AssetInfo> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.assets_info.get(name).cloned()
+    })
+}
+
+/// Sets the balance of `account` in the ledger of the fungible asset named
+/// `name`. The supplied amount of the asset is adjusted accordingly.
+///
+/// # Panics
+///
+/// If the asset is not a registered fungible asset, or the adjusted supplied
+/// amount would exceed the total supply, or be less than 0 because the
+/// supplied amount is inconsistent with the ledger.
+pub fn set_asset_balance(name: &str, account: Address, amount: u64) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let asset_info = instance
+            .assets_info
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("the asset `{}` is not registered", name));
+        assert!(asset_info.fungible, "the asset `{}` is not fungible", name);
+        let balances = instance.fungible_asset.entry(name.to_owned()).or_default();
+        let balance = balances.entry(account).or_insert(0);
+        let supplied = asset_info
+            .supplied
+            .checked_sub(*balance)
+            .unwrap_or_else(|| {
+                panic!(
+                    "the supplied amount of asset `{}` is less than the balance of {}",
+                    name, account
+                )
+            });
+        let supplied = supplied
+            .checked_add(amount)
+            .filter(|supplied| *supplied <= asset_info.total_supply)
+            .unwrap_or_else(|| {
+                panic!(
+                    "the supplied amount of asset `{}` exceeds its total supply",
+                    name
+                )
+            });
+        asset_info.supplied = supplied;
+        *balance = amount;
+    })
+}
+
+/// Makes `owner` own the token `id` of the not fungible asset named `name`,
+/// the token is taken from its previous owner if there is one.
+///
+/// # Panics
+///
+/// If the asset is not a registered not fungible asset, or the token would
+/// exceed the total supply.
+pub fn set_asset_token(name: &str, id: u64, owner: Address, uri: &str) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let asset_info = instance
+            .assets_info
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("the asset `{}` is not registered", name));
+        assert!(!asset_info.fungible, "the asset `{}` is fungible", name);
+        assert!(
+            id > 0 && id <= asset_info.total_supply,
+            "the token id of asset `{}` must be in [1, {}]",
+            name,
+            asset_info.total_supply
+        );
+        // Tokens are issued with increasing ids, so the following issued
+        // tokens will not collide with this one.
+        asset_info.supplied = asset_info.supplied.max(id);
+
+        let ledger = instance
+            .not_fungible_asset
+            .entry(name.to_owned())
+            .or_default();
+        for tokens in ledger.values_mut() {
+            tokens.remove(&id);
+        }
+        ledger.entry(owner).or_default().insert(id, uri.to_owned());
+    })
+}
+
+/// The ledger of an asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetLedger {
+    pub info: AssetInfo,
+    /// The balances of accounts, only for fungible asset.
+    pub balances: BTreeMap<Address, u64>,
+    /// The tokens owned by accounts, indexed by token id and valued by token
+    /// URI, only for not fungible asset.
+    pub tokens: BTreeMap<Address, BTreeMap<u64, String>>,
+}
+
+impl AssetLedger {
+    /// Returns the accounts which hold the asset.
+    pub fn holders(&self) -> Vec<Address> {
+        if self.info.fungible {
+            self.balances
+                .iter()
+                .filter(|(_, balance)| **balance > 0)
+                .map(|(account, _)| *account)
+                .collect()
+        } else {
+            self.tokens
+                .iter()
+                .filter(|(_, tokens)| !tokens.is_empty())
+                .map(|(account, _)| *account)
+                .collect()
+        }
+    }
+}
+
+/// Returns the ledger of the asset named `name`, if registered.
+pub fn get_asset_ledger(name: &str) -> Option<AssetLedger> {
+    dump_asset_ledgers().remove(name)
+}
+
+/// Returns the ledgers of all registered assets, indexed by asset names.
+pub fn dump_asset_ledgers() -> BTreeMap<String, AssetLedger> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance
+            .assets_info
+            .iter()
+            .map(|(name, info)| {
+                let balances = instance
+                    .fungible_asset
+                    .get(name)
+                    .map(|balances| balances.iter().map(|(k, v)| (*k, *v)).collect())
+                    .unwrap_or_default();
+                let tokens = instance
+                    .not_fungible_asset
+                    .get(name)
+                    .map(|ledger| {
+                        ledger
+                            .iter()
+                            .map(|(account, tokens)| {
+                                (*account, tokens.clone().into_iter().collect())
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let ledger = AssetLedger {
+                    info: info.clone(),
+                    balances,
+                    tokens,
+                };
+                (name.clone(), ledger)
+            })
+            .collect()
+    })
+}
+
+/// Returns the KV table named `name` created via `precompiled::KvTable`, if
+/// any. Rows are indexed by primary keys, and values are stored as strings.
+pub fn get_kv_table(name: &str) -> Option<KvTableData> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.tables.kv_tables.get(name).cloned()
+    })
+}
+
+/// Returns the CRUD table named `name` created via `precompiled::Table`, if
+/// any. Rows are kept in the order of insertion, and values are stored as
+/// strings.
+pub fn get_table(name: &str) -> Option<TableData> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.tables.tables.get(name).cloned()
+    })
+}
+
+/// Registers the contract at `address` in CNS with `name` and `version`, so
+/// that it can be resolved via `precompiled::Cns` or `Interface::at_cns`.
+///
+/// # Panics
+///
+/// If `name` and `version` have been registered, or `version` is too long.
+pub fn register_cns(name: &str, version: &str, address: Address, abi: &str) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let entry = CnsEntry {
+            address,
+            abi: abi.to_owned(),
+        };
+        let code =
+            precompiled::insert_cns(instance, name.to_owned(), version.to_owned(), entry);
+        if code != 1 {
+            panic!(
+                "fail to register `{}` of version `{}` in CNS, the error code is {}",
+                name, version, code
+            );
+        }
+    })
+}
+
+/// Returns the contract registered in CNS with `name` and `version`, if any.
+pub fn get_cns_entry(name: &str, version: &str) -> Option<CnsEntry> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance
+            .cns
+            .get(&(name.to_owned(), version.to_owned()))
+            .cloned()
+    })
+}
+
+/// Returns the accounts allowed to write the table named `table_name` via
+/// `precompiled::Permission`.
+pub fn get_write_permissions(table_name: &str) -> BTreeSet<Address> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance
+            .governance
+            .table_permissions
+            .get(table_name)
+            .cloned()
+            .unwrap_or_default()
+    })
+}
+
+/// Returns the accounts allowed to deploy contracts and create tables via
+/// `precompiled::Permission`.
+pub fn get_deploy_permissions() -> BTreeSet<Address> {
+    get_write_permissions(SYS_TABLES)
+}
+
+/// Returns the accounts allowed to write the contract at `contract` via
+/// `precompiled::Permission`.
+pub fn get_contract_write_permissions(contract: &Address) -> BTreeSet<Address> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance
+            .governance
+            .contract_permissions
+            .get(contract)
+            .cloned()
+            .unwrap_or_default()
+    })
+}
+
+/// Returns the system config `key` set via `precompiled::SystemConfig`, or its
+/// default value.
+pub fn get_system_config(key: &str) -> Option<String> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.governance.system_configs.get(key).cloned()
+    })
+}
+
+/// Returns the sealers and observers of the group indexed by node IDs, which
+/// are managed via `precompiled::Consensus`.
+pub fn get_consensus_nodes() -> BTreeMap<String, NodeType> {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.governance.consensus_nodes.clone()
+    })
+}
+
+/// Adds the node `node_id` to the group as `node_type`, e.g. the sealers of
+/// the genesis block.
+///
+/// # Panics
+///
+/// If `node_id` is invalid, or the node is the last sealer and `node_type`
+/// is `NodeType::Observer`.
+pub fn set_consensus_node(node_id: &str, node_type: NodeType) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let nodes = &mut instance.governance.consensus_nodes;
+        let code =
+            precompiled::set_consensus_node(nodes, node_id.to_owned(), Some(node_type));
+        if code != 1 {
+            panic!(
+                "fail to set `{}` as {:?}, the error code is {}",
+                node_id, node_type, code
+            );
+        }
+    })
+}
+
+/// Writes contract storage and code, blocks, emitted events, asset ledgers,
+/// tables, the CNS registry and the governance state of the off-chain
+/// environment to the file at `path` in JSON format.
+///
+/// # Note
+///
+/// Registered contracts are not included, they need to be deployed or
+/// registered again after loading.
+pub fn dump_state<P>(path: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let state =
+        <EnvInstance as OnInstance>::on_instance(|instance| instance.dump_state());
+    let json = serde_json::to_string_pretty(&state)?;
+    fs::write(path, json)
+}
+
+/// Replaces contract storage and code, blocks, emitted events, asset ledgers,
+/// tables, the CNS registry and the governance state of the off-chain
+/// environment with the ones in the file at `path`, which is written by
+/// [`dump_state`].
+pub fn load_state<P>(path: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let json = fs::read_to_string(path)?;
+    let state = serde_json::from_str(&json)?;
+    <EnvInstance as OnInstance>::on_instance(|instance| instance.load_state(state))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
 /// Returns the recorded emitted events in order.
 pub fn get_events() -> Vec<Event> {
     <EnvInstance as OnInstance>::on_instance(|instance| {
         instance.get_events().cloned().collect::<Vec<_>>()
     })
 }
+
+/// Clears the recorded emitted events, so that the events emitted by
+/// following steps can be checked separately.
+pub fn clear_events() {
+    <EnvInstance as OnInstance>::on_instance(|instance| instance.events.clear())
+}
+
+/// Returns the messages printed via `debug!` in order.
+pub fn get_debug_messages() -> Vec<String> {
+    <EnvInstance as OnInstance>::on_instance(|instance| instance.debug_messages.clone())
+}
+
+/// Clears the messages printed via `debug!`.
+pub fn clear_debug_messages() {
+    <EnvInstance as OnInstance>::on_instance(|instance| instance.debug_messages.clear())
+}
+
+/// A query of recorded emitted events of type `E`.
+pub struct EventQuery<E> {
+    topics: Vec<(usize, Hash)>,
+    _marker: PhantomData<E>,
+}
+
+/// Starts a query of recorded emitted events of type `E`, events are matched by
+/// the signature of `E`.
+pub fn query_events<E>() -> EventQuery<E>
+where
+    E: EventSignature,
+{
+    EventQuery {
+        topics: Vec::new(),
+        _marker: PhantomData,
+    }
+}
+
+impl<E> EventQuery<E>
+where
+    E: EventSignature,
+{
+    /// Only matches events whose `index`-th indexed field equals to `value`.
+    pub fn topic<T>(mut self, index: usize, value: &T) -> Self
+    where
+        T: You_Should_Use_An_Valid_Event_Topic_Type + Encode,
+    {
+        self.topics.push((index, value.topic()));
+        self
+    }
+
+    /// Returns the matched events in order.
+    pub fn fetch(&self) -> Vec<Event> {
+        let signature = E::signature();
+        <EnvInstance as OnInstance>::on_instance(|instance| {
+            instance
+                .get_events()
+                .filter(|event| event.signature() == Some(&signature))
+                .filter(|event| {
+                    self.topics
+                        .iter()
+                        .all(|(index, topic)| event.topics.get(index + 1) == Some(topic))
+                })
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Returns the number of matched events.
+    pub fn count(&self) -> usize {
+        self.fetch().len()
+    }
+}
+
+/// Checks whether `event` has been emitted, and returns a readable message if
+/// the result is not as `expected`. Used by `assert_emitted!` and
+/// `assert_not_emitted!`.
+#[doc(hidden)]
+pub fn check_emitted<E>(event: E, expected: bool) -> Result<(), String>
+where
+    E: Topics + Encode,
+{
+    let event = Event::new(event);
+    let events = get_events();
+    let emitted = events.contains(&event);
+    if emitted == expected {
+        return Ok(());
+    }
+
+    if expected {
+        let similar = events
+            .iter()
+            .filter(|recorded| recorded.signature() == event.signature())
+            .map(|recorded| format!("\n    {:?}", recorded))
+            .collect::<String>();
+        Err(format!(
+            "assertion failed: event is emitted\n  expected: {:?}\n  recorded events \
+             with the same signature:{}",
+            event,
+            if similar.is_empty() {
+                String::from(" none")
+            } else {
+                similar
+            }
+        ))
+    } else {
+        Err(format!(
+            "assertion failed: event is not emitted\n  unexpected: {:?}",
+            event
+        ))
+    }
+}
+
+/// Asserts that an event equal to the given one has been emitted.
+///
+/// # Example
+///
+/// ```ignore
+/// assert_emitted!(Transfer { from: alice, to: bob, value: 10 });
+/// ```
+#[macro_export]
+macro_rules! assert_emitted {
+    ($event:expr $(,)?) => {
+        if let Err(msg) = $crate::env::test::check_emitted($event, true) {
+            panic!("{}", msg);
+        }
+    };
+}
+
+/// Asserts that no event equal to the given one has been emitted.
+#[macro_export]
+macro_rules! assert_not_emitted {
+    ($event:expr $(,)?) => {
+        if let Err(msg) = $crate::env::test::check_emitted($event, false) {
+            panic!("{}", msg);
+        }
+    };
+}
@@ -0,0 +1,95 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{doubler, test_api, test_api::AccountKind, DOUBLER};
+use crate::{lang_core::env, EnvAccess};
+use cfg_if::cfg_if;
+use liquid_primitives::hash::hash;
+
+#[test]
+fn account_kinds() {
+    let accounts = test_api::default_accounts();
+    assert_eq!(
+        test_api::get_account_kind(&accounts.alice),
+        AccountKind::ExternallyOwned
+    );
+    assert_eq!(env::get_external_code_size(&accounts.alice), 0);
+    assert!(!EnvAccess {}.is_contract(&accounts.alice));
+
+    test_api::set_contract_code_size(accounts.bob, 42);
+    assert_eq!(env::get_external_code_size(&accounts.bob), 42);
+    assert!(EnvAccess {}.is_contract(&accounts.bob));
+
+    test_api::register_contract(DOUBLER, doubler);
+    assert!(EnvAccess {}.is_contract(&DOUBLER));
+    test_api::set_externally_owned(DOUBLER);
+    assert!(!EnvAccess {}.is_contract(&DOUBLER));
+    assert!(!test_api::is_contract_registered(&DOUBLER));
+}
+
+#[test]
+fn derive_accounts() {
+    let factory = test_api::AccountFactory::new(b"seed");
+    let accounts = factory.accounts(3);
+    assert_eq!(accounts[1], factory.account(1));
+    assert_ne!(accounts[0].address, accounts[1].address);
+    assert_ne!(accounts[1].address, accounts[2].address);
+
+    let mut other = test_api::AccountFactory::new(b"seed");
+    assert_eq!(other.next_account(), accounts[0]);
+    assert_eq!(other.next_account(), accounts[1]);
+    assert_ne!(
+        test_api::AccountFactory::new(b"other").account(0),
+        accounts[0]
+    );
+
+    let account = &accounts[0];
+    assert_eq!(
+        &hash(&account.public_key)[12..],
+        &account.address.0[..],
+        "the address must be derived from the public key"
+    );
+    assert_eq!(
+        test_api::Account::from_secret_key(account.secret_key).as_ref(),
+        Some(account)
+    );
+    assert_eq!(test_api::Account::from_secret_key([0x00; 32]), None);
+    assert_eq!(test_api::Account::from_secret_key([0xff; 32]), None);
+}
+
+#[test]
+fn sign_with_derived_account() {
+    let account = test_api::AccountFactory::new(b"seed").account(0);
+    let message_hash = hash(b"hello");
+    let signature = account.sign(&message_hash);
+    assert_eq!(account.sign_message(b"hello").r.len(), 32);
+
+    cfg_if! {
+        if #[cfg(not(feature = "gm"))] {
+            assert_eq!(signature, account.sign_message(b"hello"));
+            assert_eq!(signature.to_bytes().len(), 65);
+            let mut compact = [0u8; 64];
+            compact[..32].copy_from_slice(&signature.r);
+            compact[32..].copy_from_slice(&signature.s);
+            let public_key = secp256k1::recover(
+                &secp256k1::Message::parse(&message_hash),
+                &secp256k1::Signature::parse(&compact),
+                &secp256k1::RecoveryId::parse(signature.v - 27).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(&public_key.serialize()[1..], &account.public_key[..]);
+        } else {
+            assert_eq!(signature.to_bytes().len(), 128);
+            assert_eq!(signature.public_key, account.public_key);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{test_api, EnvInstance};
+use crate::lang_core::env::{self, engine::OnInstance};
+
+#[test]
+fn seed_fungible_asset() {
+    let accounts = test_api::default_accounts();
+    test_api::register_asset("Token", accounts.alice, true, 100, "a token");
+    test_api::set_asset_balance("Token", accounts.bob, 30);
+    test_api::set_asset_balance("Token", accounts.charlie, 20);
+    test_api::set_asset_balance("Token", accounts.charlie, 10);
+    assert_eq!(env::get_asset_balance(&accounts.bob, b"Token"), 30);
+
+    let info = test_api::get_asset_info("Token").unwrap();
+    assert_eq!(info.description, "a token");
+    assert_eq!(info.supplied, 40);
+
+    test_api::set_caller(accounts.bob);
+    assert!(env::transfer_asset(&accounts.david, b"Token", 5, false));
+    let ledger = test_api::get_asset_ledger("Token").unwrap();
+    assert_eq!(
+        ledger.holders(),
+        vec![accounts.bob, accounts.charlie, accounts.david]
+    );
+    assert_eq!(ledger.balances[&accounts.david], 5);
+    assert!(ledger.tokens.is_empty());
+    test_api::pop_execution_context();
+}
+
+#[test]
+#[should_panic(expected = "the supplied amount of asset `Token` exceeds its total supply")]
+fn seed_fungible_asset_over_supply() {
+    let accounts = test_api::default_accounts();
+    test_api::register_asset("Token", accounts.alice, true, 100, "");
+    test_api::set_asset_balance("Token", accounts.bob, 101);
+}
+
+#[test]
+#[should_panic(expected = "the supplied amount of asset `Token` exceeds its total supply")]
+fn seed_fungible_asset_overflowing_supply() {
+    let accounts = test_api::default_accounts();
+    test_api::register_asset("Token", accounts.alice, true, 100, "");
+    test_api::set_asset_balance("Token", accounts.bob, 1);
+    test_api::set_asset_balance("Token", accounts.charlie, u64::MAX);
+}
+
+#[test]
+#[should_panic(expected = "the supplied amount of asset `Token` is less than the balance")]
+fn seed_fungible_asset_inconsistent_supply() {
+    let accounts = test_api::default_accounts();
+    test_api::register_asset("Token", accounts.alice, true, 100, "");
+    test_api::set_asset_balance("Token", accounts.bob, 30);
+
+    // The ledger is now inconsistent with the supplied amount on purpose.
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.assets_info.get_mut("Token").unwrap().supplied = 20;
+    });
+    test_api::set_asset_balance("Token", accounts.bob, 0);
+}
+
+#[test]
+fn issue_fungible_asset_over_supply() {
+    let accounts = test_api::default_accounts();
+    test_api::register_asset("Token", accounts.alice, true, 100, "");
+    test_api::set_asset_balance("Token", accounts.bob, 30);
+    test_api::set_caller(accounts.alice);
+    assert!(env::issue_fungible_asset(&accounts.bob, b"Token", 70));
+    assert!(!env::issue_fungible_asset(&accounts.bob, b"Token", 1));
+
+    // A supplied amount over the total supply is rejected instead of
+    // underflowing the remaining amount.
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.assets_info.get_mut("Token").unwrap().supplied = 101;
+    });
+    assert!(!env::issue_fungible_asset(&accounts.bob, b"Token", 1));
+    test_api::pop_execution_context();
+}
+
+#[test]
+fn seed_not_fungible_asset() {
+    let accounts = test_api::default_accounts();
+    test_api::register_asset("Art", accounts.alice, false, 10, "");
+    test_api::set_asset_token("Art", 3, accounts.bob, "ipfs://3");
+    test_api::set_asset_token("Art", 3, accounts.charlie, "ipfs://3");
+    assert_eq!(env::get_asset_balance(&accounts.bob, b"Art"), 0);
+    assert_eq!(
+        env::get_not_fungible_asset_ids(&accounts.charlie, b"Art"),
+        vec![3]
+    );
+
+    test_api::set_caller(accounts.alice);
+    assert_eq!(
+        env::issue_not_fungible_asset(&accounts.alice, b"Art", b""),
+        4
+    );
+    test_api::pop_execution_context();
+
+    let ledgers = test_api::dump_asset_ledgers();
+    assert_eq!(ledgers.len(), 1);
+    assert_eq!(
+        ledgers["Art"].holders(),
+        vec![accounts.charlie, accounts.alice]
+    );
+    assert_eq!(ledgers["Art"].tokens[&accounts.charlie][&3], "ipfs://3");
+}
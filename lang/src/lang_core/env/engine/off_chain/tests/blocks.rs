@@ -0,0 +1,59 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{block_number, test_api};
+use crate::lang_core::env;
+
+#[test]
+fn deterministic_block_clock() {
+    assert_eq!(env::now(), 0);
+    assert_eq!(block_number(), 0);
+
+    test_api::set_genesis_timestamp(1_000);
+    assert_eq!(env::now(), 1_000);
+    assert_eq!(block_number(), 0);
+
+    test_api::advance_block();
+    assert_eq!(env::now(), 1_001);
+    assert_eq!(block_number(), 1);
+
+    test_api::advance_blocks(10);
+    assert_eq!(env::now(), 1_011);
+    assert_eq!(block_number(), 11);
+
+    test_api::set_next_block_number(100);
+    test_api::set_next_block_timestamp(2_000);
+    test_api::advance_block();
+    assert_eq!(env::now(), 2_000);
+    assert_eq!(block_number(), 100);
+
+    test_api::advance_block();
+    assert_eq!(env::now(), 2_001);
+    assert_eq!(block_number(), 101);
+}
+
+#[test]
+fn wall_clock() {
+    test_api::advance_block();
+    test_api::use_wall_clock();
+    assert!(env::now() > 1_600_000_000);
+    assert_eq!(block_number(), 0);
+}
+
+#[test]
+#[should_panic(
+    expected = "the timestamp of next block must not be earlier than current one"
+)]
+fn next_block_timestamp_goes_backwards() {
+    test_api::set_genesis_timestamp(1_000);
+    test_api::set_next_block_timestamp(999);
+}
@@ -0,0 +1,184 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    call_data_of, doubler, forwarder, inspector, reader, reverter, storage_of, test_api,
+    Encode, DOUBLER, FORWARDER, READER, REVERTER,
+};
+use crate::{
+    lang_core::env::{
+        self,
+        error::{CallErrorKind, EnvError},
+        CallError,
+    },
+    DispatchError, EnvAccess,
+};
+use liquid_primitives::types::Address;
+
+#[test]
+fn call_registered_contract() {
+    test_api::register_contract(DOUBLER, doubler);
+    assert_eq!(test_api::is_contract_registered(&DOUBLER), true);
+    assert_eq!(env::call::<u32>(&DOUBLER, &call_data_of(21)).ok(), Some(42));
+}
+
+#[test]
+fn storage_is_isolated() {
+    test_api::register_contract(DOUBLER, doubler);
+    let _ = env::call::<u32>(&DOUBLER, &call_data_of(1));
+    assert!(env::api::get_storage::<u32>(b"input").is_err());
+    assert_eq!(storage_of::<u32>(DOUBLER, b"input"), Some(1));
+}
+
+#[test]
+fn call_unregistered_contract() {
+    assert_eq!(test_api::is_contract_registered(&DOUBLER), false);
+    assert!(env::call::<u32>(&DOUBLER, &call_data_of(1)).is_err());
+}
+
+#[test]
+fn call_with_invalid_call_data() {
+    test_api::register_contract(DOUBLER, doubler);
+    assert!(env::call::<u32>(&DOUBLER, &[0x00; 3]).is_err());
+}
+
+#[test]
+fn callee_reverts() {
+    test_api::register_contract(REVERTER, reverter);
+    assert!(env::call::<u32>(&REVERTER, &call_data_of(1)).is_err());
+    assert_eq!(storage_of::<u32>(REVERTER, b"input"), None);
+}
+
+#[test]
+fn revert_reason_is_propagated() {
+    test_api::register_contract(REVERTER, reverter);
+    let error = match env::call::<u32>(&REVERTER, &call_data_of(1)) {
+        Err(EnvError::FailToCallForeignContract(error)) => error,
+        _ => panic!("the call should fail"),
+    };
+    assert_eq!(error.reason.as_deref(), Some("always revert"));
+    assert_eq!(error.return_data, String::from("always revert").encode());
+    assert_eq!(
+        CallError::from_return_data(error.return_data.clone()),
+        error
+    );
+
+    match env::call::<u32>(&DOUBLER, &call_data_of(1)) {
+        Err(EnvError::FailToCallForeignContract(error)) => {
+            assert_eq!(error, CallError::default())
+        }
+        _ => panic!("the call should fail"),
+    }
+}
+
+#[test]
+fn call_error_kind() {
+    test_api::register_contract(DOUBLER, doubler);
+    test_api::register_contract(REVERTER, reverter);
+    let error =
+        CallError::from(env::call::<u32>(&REVERTER, &call_data_of(1)).unwrap_err());
+    assert_eq!(error.kind, CallErrorKind::Reverted);
+
+    // The output of the callee can't be decoded as the expected type, which is
+    // not a revert even though there is no revert reason either.
+    let error = CallError::from(
+        env::call::<(u32, u32, u32)>(&DOUBLER, &call_data_of(1)).unwrap_err(),
+    );
+    assert_eq!(error.kind, CallErrorKind::Decode);
+    assert_eq!(error.reason, None);
+
+    let error = CallError::from(EnvError::Unsupported);
+    assert_eq!(error.kind, CallErrorKind::Unsupported);
+}
+
+#[cfg(feature = "solidity-compatible")]
+#[test]
+fn decode_solidity_revert_reason() {
+    let mut return_data = env::error::REVERT_SELECTOR.to_vec();
+    return_data.extend(String::from("insufficient balance").encode());
+    let error = CallError::from_return_data(return_data);
+    assert_eq!(error.reason.as_deref(), Some("insufficient balance"));
+
+    let error = CallError::from_return_data(vec![0xff; 3]);
+    assert_eq!(error.reason, None);
+}
+
+#[test]
+fn static_call() {
+    test_api::register_contract(DOUBLER, doubler);
+    test_api::register_contract(READER, reader);
+    test_api::register_contract(FORWARDER, forwarder);
+    assert_eq!(
+        env::static_call::<u32>(&READER, &call_data_of(0)).ok(),
+        Some(0)
+    );
+
+    let error =
+        CallError::from(env::static_call::<u32>(&DOUBLER, &call_data_of(1)).unwrap_err());
+    assert_eq!(
+        error.reason.as_deref(),
+        Some("writing storage is not allowed in static call")
+    );
+    assert_eq!(storage_of::<u32>(DOUBLER, b"input"), None);
+
+    // The calls made by the callee of a static call are static as well.
+    assert!(env::static_call::<u32>(&FORWARDER, &call_data_of(0)).is_err());
+    assert_eq!(env::call::<u32>(&FORWARDER, &call_data_of(0)).ok(), Some(2));
+    assert_eq!(
+        env::static_call::<u32>(&READER, &call_data_of(0)).ok(),
+        Some(0)
+    );
+}
+
+#[test]
+fn dispatch_call() {
+    test_api::set_caller_callee(Default::default(), DOUBLER);
+    assert_eq!(
+        test_api::dispatch_call(doubler, &call_data_of(4)),
+        Ok(8u32.encode())
+    );
+    assert_eq!(env::api::get_storage::<u32>(b"input").ok(), Some(4));
+    assert_eq!(
+        test_api::dispatch_call(doubler, &[0x00; 4]),
+        Err(DispatchError::InvalidParams)
+    );
+    test_api::pop_execution_context();
+}
+
+#[test]
+fn block_and_call_context() {
+    const INSPECTOR: Address = Address::new([0x44; 20]);
+    let accounts = test_api::default_accounts();
+    test_api::register_contract(INSPECTOR, inspector);
+    test_api::set_caller(accounts.alice);
+    test_api::advance_blocks(2);
+
+    assert_eq!(EnvAccess {}.get_block_number(), 2);
+    assert_eq!(EnvAccess {}.get_call_depth().ok(), Some(0));
+    assert_eq!(EnvAccess {}.get_call_data_size(), 0);
+
+    let data = call_data_of(7);
+    let _ = env::call::<()>(&INSPECTOR, &data);
+    assert_eq!(
+        storage_of::<(u64, u32, u32)>(INSPECTOR, b"context"),
+        Some((2, data.len() as u32, 1))
+    );
+
+    // Constructors are executed one level deeper, the same as calls.
+    const DEPLOYED: Address = Address::new([0x45; 20]);
+    test_api::deploy_contract(DEPLOYED, reader, || {
+        assert_eq!(EnvAccess {}.get_call_depth().ok(), Some(1));
+        assert_eq!(env::get_caller(), accounts.alice);
+    });
+    assert_eq!(EnvAccess {}.get_call_depth().ok(), Some(0));
+    test_api::pop_execution_context();
+}
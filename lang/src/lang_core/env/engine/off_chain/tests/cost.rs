@@ -0,0 +1,85 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    call_data_of, doubler, test_api, test_api::CostTable, transfer, Encode, DOUBLER,
+};
+use crate::lang_core::env;
+
+/// Makes each storage write cost 10, regardless of its size.
+fn set_write_cost() {
+    test_api::set_cost_table(CostTable {
+        storage_write: 10,
+        storage_write_byte: 0,
+        ..Default::default()
+    });
+}
+
+#[test]
+fn measure_cost() {
+    let cost_table = CostTable::default();
+    let (_, cost) = test_api::measure_cost(|| {
+        env::api::set_storage(b"value", &1u32);
+        let _ = env::api::get_storage::<u32>(b"value");
+        env::api::remove_storage(b"value");
+        env::emit(transfer("alice", 1));
+    });
+    assert_eq!(cost.storage_writes, 1);
+    assert_eq!(cost.storage_write_bytes, 9);
+    assert_eq!(cost.storage_reads, 1);
+    assert_eq!(cost.storage_read_bytes, 9);
+    assert_eq!(cost.storage_removals, 1);
+    assert_eq!(cost.events, 1);
+    assert_eq!(cost.event_topics, 2);
+    let event_bytes = transfer("alice", 1).encode().len() as u64;
+    assert_eq!(cost.event_bytes, event_bytes);
+    assert_eq!(
+        cost.total,
+        cost_table.storage_write
+            + cost_table.storage_write_byte * 9
+            + cost_table.storage_read
+            + cost_table.storage_read_byte * 9
+            + cost_table.storage_remove
+            + cost_table.event
+            + cost_table.event_byte * event_bytes
+            + cost_table.event_topic * 2
+    );
+
+    test_api::register_contract(DOUBLER, doubler);
+    let (_, cost) = test_api::measure_cost(|| {
+        let _ = env::call::<u32>(&DOUBLER, &call_data_of(1));
+    });
+    assert_eq!(cost.calls, 1);
+    assert_eq!(cost.storage_writes, 1);
+}
+
+#[test]
+fn within_budget() {
+    set_write_cost();
+    let ret = test_api::with_budget(20, || {
+        env::api::set_storage(b"a", &1u32);
+        env::api::set_storage(b"b", &1u32);
+        42
+    });
+    assert_eq!(ret, 42);
+}
+
+#[test]
+#[should_panic(expected = "out of budget: the cost 30 exceeds the budget 20")]
+fn out_of_budget() {
+    set_write_cost();
+    test_api::with_budget(20, || {
+        env::api::set_storage(b"a", &1u32);
+        env::api::set_storage(b"b", &1u32);
+        env::api::set_storage(b"c", &1u32);
+    });
+}
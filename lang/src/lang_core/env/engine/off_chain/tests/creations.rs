@@ -0,0 +1,80 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    call_data_of, doubler, initializer, storage_of, test_api, test_api::AccountKind,
+    Encode,
+};
+use crate::{
+    lang_core::env::{self, CallError},
+    EnvAccess,
+};
+use liquid_primitives::types::Address;
+
+const CODE: &[u8] = b"doubler";
+
+#[test]
+fn create_contract() {
+    test_api::register_code(CODE.to_vec(), initializer, doubler, &[]);
+
+    let (created, cost) = test_api::measure_cost(|| env::create(CODE, &7u32.encode()));
+    let created = created.unwrap();
+    assert_eq!(cost.creations, 1);
+    assert_eq!(cost.created_code_bytes, CODE.len() as u64);
+    assert_eq!(
+        test_api::get_account_kind(&created),
+        AccountKind::Contract {
+            code: CODE.to_vec()
+        }
+    );
+    assert_eq!(storage_of::<u32>(created, b"input"), Some(7));
+    assert_eq!(env::call::<u32>(&created, &call_data_of(21)).ok(), Some(42));
+
+    let another = EnvAccess {}.create(CODE, &1u32.encode()).unwrap();
+    assert_ne!(another, created);
+
+    // The addresses are derived from the creator and the number of contracts
+    // it has created.
+    test_api::reset();
+    test_api::register_code(CODE.to_vec(), initializer, doubler, &[]);
+    assert_eq!(env::create(CODE, &7u32.encode()).ok(), Some(created));
+
+    let error = EnvAccess {}.create(CODE, &0u32.encode()).unwrap_err();
+    assert_eq!(error.reason.as_deref(), Some("zero input"));
+    assert_eq!(
+        EnvAccess {}.create(b"unknown", &[]),
+        Err(CallError::default())
+    );
+}
+
+#[test]
+fn reverted_create() {
+    test_api::register_code(CODE.to_vec(), initializer, doubler, &[]);
+
+    let mut reverted = Address::default();
+    let result = test_api::transact(|| {
+        reverted = env::create(CODE, &7u32.encode()).unwrap();
+        assert_eq!(
+            env::call::<u32>(&reverted, &call_data_of(21)).ok(),
+            Some(42)
+        );
+        env::revert(&String::from("oops"));
+    });
+    assert!(result.is_err());
+    assert!(!EnvAccess {}.is_contract(&reverted));
+    assert!(env::call::<u32>(&reverted, &call_data_of(21)).is_err());
+    assert_eq!(storage_of::<u32>(reverted, b"input"), None);
+
+    // The nonce of the creator is restored as well, so the next contract
+    // takes the address of the reverted one.
+    assert_eq!(env::create(CODE, &7u32.encode()).ok(), Some(reverted));
+}
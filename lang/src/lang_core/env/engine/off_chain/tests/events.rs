@@ -0,0 +1,60 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{test_api, transfer, Transfer};
+use crate::lang_core::env;
+
+#[test]
+fn query_events() {
+    env::emit(transfer("alice", 1));
+    env::emit(transfer("bob", 2));
+    env::emit(transfer("alice", 3));
+
+    let events = test_api::query_events::<Transfer>().fetch();
+    assert_eq!(events.len(), 3);
+    let events = test_api::query_events::<Transfer>()
+        .topic(0, &String::from("alice"))
+        .fetch();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].decode_data::<u32>(), 3);
+
+    test_api::clear_events();
+    assert_eq!(test_api::query_events::<Transfer>().count(), 0);
+}
+
+#[test]
+fn assert_emitted() {
+    env::emit(transfer("alice", 1));
+    assert_emitted!(transfer("alice", 1));
+    assert_not_emitted!(transfer("alice", 2));
+    assert_not_emitted!(transfer("bob", 1));
+}
+
+#[test]
+#[should_panic(expected = "recorded events with the same signature")]
+fn assert_emitted_fails() {
+    env::emit(transfer("alice", 1));
+    assert_emitted!(transfer("alice", 2));
+}
+
+#[test]
+fn debug_messages() {
+    let value = 42;
+    crate::debug!("value: {}", value);
+    crate::debug!("done");
+    assert_eq!(
+        test_api::get_debug_messages(),
+        vec![String::from("value: 42"), String::from("done")]
+    );
+    test_api::clear_debug_messages();
+    assert!(test_api::get_debug_messages().is_empty());
+}
@@ -0,0 +1,161 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod accounts;
+mod assets;
+mod blocks;
+mod calls;
+mod cost;
+mod creations;
+mod events;
+mod precompiled;
+mod snapshots;
+mod state;
+mod traces;
+mod transactions;
+
+use super::{test_api, EnvInstance};
+use crate::{
+    lang_core::env::{self, backend::Env, engine::OnInstance, CallMode},
+    DispatchError, DispatchResult, EnvAccess, You_Should_Use_An_Valid_Event_Topic_Type,
+};
+use cfg_if::cfg_if;
+use liquid_primitives::{
+    hash::hash,
+    types::{Address, Hash},
+    EventSignature, Topics,
+};
+
+cfg_if! {
+    if #[cfg(feature = "solidity-compatible")] {
+        use liquid_abi_codec::{Decode, Encode};
+    } else {
+        use scale::{Decode, Encode};
+    }
+}
+
+const DOUBLER: Address = Address::new([0x11; 20]);
+
+fn doubler() -> DispatchResult {
+    let call_data = env::get_call_data(CallMode::Call)
+        .map_err(|_| DispatchError::CouldNotReadInput)?;
+    let input = <u32 as Decode>::decode(&mut call_data.data.as_slice())
+        .map_err(|_| DispatchError::InvalidParams)?;
+    env::api::set_storage(b"input", &input);
+    env::finish(&(input * 2));
+    Ok(())
+}
+
+fn reverter() -> DispatchResult {
+    env::api::set_storage(b"input", &0u32);
+    env::revert(&String::from("always revert"));
+    Ok(())
+}
+
+const REVERTER: Address = Address::new([0x22; 20]);
+
+const FORWARDER: Address = Address::new([0x33; 20]);
+
+fn forwarder() -> DispatchResult {
+    let output = env::call::<u32>(&DOUBLER, &call_data_of(1))
+        .map_err(|_| DispatchError::CouldNotReadInput)?;
+    env::emit(transfer("alice", output));
+    let _ = env::call::<u32>(&REVERTER, &call_data_of(1));
+    env::finish(&output);
+    Ok(())
+}
+
+fn inspector() -> DispatchResult {
+    let context = (
+        EnvAccess {}.get_block_number(),
+        EnvAccess {}.get_call_data_size(),
+        EnvAccess {}.get_call_depth().unwrap(),
+    );
+    env::api::set_storage(b"context", &context);
+    Ok(())
+}
+
+const READER: Address = Address::new([0x55; 20]);
+
+fn reader() -> DispatchResult {
+    let input = env::api::get_storage::<u32>(b"input").unwrap_or_default();
+    env::finish(&input);
+    Ok(())
+}
+
+fn initializer() -> DispatchResult {
+    let call_data = env::get_call_data(CallMode::Deploy)
+        .map_err(|_| DispatchError::CouldNotReadInput)?;
+    let input = <u32 as Decode>::decode(&mut call_data.data.as_slice())
+        .map_err(|_| DispatchError::InvalidParams)?;
+    if input == 0 {
+        env::revert(&String::from("zero input"));
+    }
+    env::api::set_storage(b"input", &input);
+    Ok(())
+}
+
+fn block_number() -> u64 {
+    <EnvInstance as OnInstance>::on_instance(|instance| instance.get_block_number())
+}
+
+/// Reads the value at `key` in the storage of `contract`.
+fn storage_of<T: scale::Decode>(contract: Address, key: &[u8]) -> Option<T> {
+    test_api::set_caller_callee(Default::default(), contract);
+    let value = env::api::get_storage::<T>(key).ok();
+    test_api::pop_execution_context();
+    value
+}
+
+/// Registers a fungible asset named `Token` with a total supply of 100 and
+/// issues 10 of it to `issuer`, who is left as the caller.
+fn issue_token(issuer: Address) {
+    test_api::set_caller(issuer);
+    assert!(env::register_asset(b"Token", &issuer, true, 100, b""));
+    assert!(env::issue_fungible_asset(&issuer, b"Token", 10));
+}
+
+struct Transfer {
+    to: String,
+    value: u32,
+}
+
+impl EventSignature for Transfer {
+    fn signature() -> Hash {
+        hash(b"Transfer").into()
+    }
+}
+
+impl Topics for Transfer {
+    fn topics(&self) -> Vec<Hash> {
+        vec![Self::signature(), self.to.topic()]
+    }
+}
+
+impl Encode for Transfer {
+    fn encode(&self) -> Vec<u8> {
+        self.value.encode()
+    }
+}
+
+fn transfer(to: &str, value: u32) -> Transfer {
+    Transfer {
+        to: to.to_owned(),
+        value,
+    }
+}
+
+fn call_data_of(input: u32) -> Vec<u8> {
+    let mut data = vec![0x00; 4];
+    data.extend(input.encode());
+    data
+}
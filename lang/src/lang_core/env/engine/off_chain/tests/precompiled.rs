@@ -0,0 +1,366 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{test_api, test_api::NodeType, DOUBLER, READER};
+use crate::{
+    lang_core::{
+        env,
+        precompiled::{
+            Cns, Condition, Consensus, KvTable, Permission, SystemConfig, Table,
+            TX_COUNT_LIMIT, TX_GAS_LIMIT,
+        },
+    },
+    DispatchError, DispatchResult, EnvAccess,
+};
+use cfg_if::cfg_if;
+use liquid_primitives::{
+    hash::hash,
+    types::{i256, u256, Address, Bytes32},
+};
+
+#[test]
+fn crypto_intrinsics() {
+    use hex_literal::hex;
+
+    assert_eq!(
+        EnvAccess {}.keccak256(b""),
+        Bytes32(hex!(
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        ))
+    );
+    assert_eq!(
+        EnvAccess {}.sm3(b"abc"),
+        Bytes32(hex!(
+            "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0"
+        ))
+    );
+    assert_eq!(
+        EnvAccess {}.sha256(b"abc"),
+        Bytes32(hex!(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        ))
+    );
+
+    let account = test_api::AccountFactory::new(b"seed").account(0);
+    let message_hash = hash(b"order");
+    let signature = account.sign(&message_hash);
+    let other = Bytes32(hash(b"other"));
+    let (hash, r, s) = (
+        Bytes32(message_hash),
+        Bytes32(signature.r),
+        Bytes32(signature.s),
+    );
+
+    cfg_if! {
+        if #[cfg(not(feature = "gm"))] {
+            assert_eq!(
+                EnvAccess {}.ecrecover(&hash, signature.v, &r, &s),
+                Some(account.address)
+            );
+            assert_ne!(
+                EnvAccess {}.ecrecover(&other, signature.v, &r, &s),
+                Some(account.address)
+            );
+            assert_eq!(EnvAccess {}.ecrecover(&hash, 0, &r, &s), None);
+        } else {
+            assert_eq!(
+                EnvAccess {}.sm2_verify(&hash, &account.public_key, &r, &s),
+                Some(account.address)
+            );
+            assert_eq!(
+                EnvAccess {}.sm2_verify(&other, &account.public_key, &r, &s),
+                None
+            );
+        }
+    }
+}
+
+#[test]
+fn kv_table() {
+    assert_eq!(
+        KvTable::create_table("t_kv", "id", &["price", "name"]),
+        Some(0.into())
+    );
+    assert_eq!(
+        KvTable::create_table("t_kv", "id", &["price"]),
+        Some((-50001).into())
+    );
+    assert!(KvTable::open_table("t_missing").is_none());
+
+    let table = KvTable::open_table("t_kv").unwrap();
+    assert!(table.get("apple").is_none());
+    let entry = table.new_entry().unwrap();
+    entry
+        .set("price", i256::from(-3))
+        .unwrap()
+        .set("name", String::from("fuji"))
+        .unwrap();
+    assert_eq!(table.set("apple", &entry), Some(1.into()));
+
+    let entry = table.get("apple").unwrap();
+    assert_eq!(entry.get::<i256>("price"), Some((-3).into()));
+    assert_eq!(entry.get::<String>("name"), Some(String::from("fuji")));
+    assert_eq!(entry.get::<String>("id"), Some(String::from("apple")));
+    assert_eq!(entry.get::<Address>("missing"), Some(Address::default()));
+
+    let rows = test_api::get_kv_table("t_kv").unwrap().rows;
+    assert_eq!(rows["apple"]["price"], "-3");
+
+    // Unknown fields revert the call, and nothing is written.
+    let entry = table.new_entry().unwrap();
+    entry.set("color", String::from("red")).unwrap();
+    assert_eq!(table.set("pear", &entry), None);
+    assert!(table.get("pear").is_none());
+
+    // Tables can't be written in static calls.
+    fn writer() -> DispatchResult {
+        let table = KvTable::open_table("t_kv").unwrap();
+        let entry = table.new_entry().unwrap();
+        table
+            .set("pear", &entry)
+            .map(|_| ())
+            .ok_or(DispatchError::InvalidParams)
+    }
+    const WRITER: Address = Address::new([0x66; 20]);
+    test_api::register_contract(WRITER, writer);
+    assert!(env::static_call::<()>(&WRITER, &[]).is_err());
+    assert!(table.get("pear").is_none());
+    assert!(env::call::<()>(&WRITER, &[]).is_ok());
+    assert!(table.get("pear").is_some());
+}
+
+#[test]
+fn crud_table() {
+    assert_eq!(
+        Table::create_table("t_fruit", "kind", &["name", "price"]),
+        Some(0.into())
+    );
+    let table = Table::open_table("t_fruit").unwrap();
+    for (name, price) in &[("fuji", 3), ("gala", 5), ("pink", 7)] {
+        let entry = table.new_entry().unwrap();
+        entry
+            .set("name", String::from(*name))
+            .unwrap()
+            .set("price", i256::from(*price))
+            .unwrap();
+        assert_eq!(table.insert("apple", &entry), Some(1.into()));
+    }
+    let entry = table.new_entry().unwrap();
+    entry.set("name", String::from("bartlett")).unwrap();
+    assert_eq!(table.insert("pear", &entry), Some(1.into()));
+
+    let names = |condition: &Condition| {
+        table
+            .select("apple", condition)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.get::<String>("name").unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&Condition::new()), ["fuji", "gala", "pink"]);
+    assert_eq!(
+        names(&Condition::new().gt("price", 3.into())),
+        ["gala", "pink"]
+    );
+    assert_eq!(
+        names(&Condition::new().le("price", 5.into())),
+        ["fuji", "gala"]
+    );
+    assert_eq!(names(&Condition::new().eq("name", "gala")), ["gala"]);
+    assert_eq!(
+        names(&Condition::new().eq("price", i256::from(7))),
+        ["pink"]
+    );
+    assert_eq!(
+        names(&Condition::new().ne("name", "gala")),
+        ["fuji", "pink"]
+    );
+    assert_eq!(names(&Condition::new().limit(1, 1)), ["gala"]);
+    assert_eq!(
+        names(
+            &Condition::new()
+                .ge("price", 3.into())
+                .lt("price", 7.into())
+                .limit(1, 5)
+        ),
+        ["gala"]
+    );
+
+    let entries = table.select("pear", &Condition::new()).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(
+        entries.get(0).unwrap().get::<String>("kind"),
+        Some("pear".into())
+    );
+    assert!(entries.get(1).is_none());
+
+    let entry = table.new_entry().unwrap();
+    entry.set("price", i256::from(10)).unwrap();
+    let condition = Condition::new().lt("price", 6.into());
+    assert_eq!(table.update("apple", &entry, &condition), Some(2.into()));
+    assert_eq!(
+        names(&Condition::new().eq("price", i256::from(10))),
+        ["fuji", "gala"]
+    );
+
+    assert_eq!(
+        table.remove("apple", &Condition::new().eq("name", "fuji")),
+        Some(1.into())
+    );
+    let rows = test_api::get_table("t_fruit").unwrap().rows;
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0]["name"], "gala");
+    assert_eq!(rows[0]["price"], "10");
+
+    // Unknown fields revert the call.
+    let entry = table.new_entry().unwrap();
+    entry.set("color", String::from("red")).unwrap();
+    assert_eq!(table.insert("apple", &entry), None);
+    assert_eq!(test_api::get_table("t_fruit").unwrap().rows.len(), 3);
+}
+
+#[test]
+fn cns_registry() {
+    let (counter, user) = (DOUBLER, READER);
+    test_api::register_cns("Counter", "1.0", counter, "[]");
+    assert_eq!(Cns::resolve("Counter", "1.0"), Some(counter));
+    assert_eq!(Cns::resolve("Counter", "2.0"), None);
+    assert_eq!(
+        Cns::get_contract_address("User".into(), "1.0".into()),
+        Some(Address::default())
+    );
+
+    let insert = |name: &str, version: &str, address: Address| {
+        Cns::insert(
+            name.into(),
+            version.into(),
+            address.to_string(),
+            "[]".into(),
+        )
+    };
+    assert_eq!(insert("User", "1.0", user), Some(1u8.into()));
+    assert_eq!(Cns::resolve("User", "1.0"), Some(user));
+    assert_eq!(
+        test_api::get_cns_entry("User", "1.0").unwrap().address,
+        user
+    );
+
+    // Error codes are returned in two's complement.
+    let max = u256::from([0xff; 32]);
+    assert_eq!(
+        insert("User", "1.0", counter),
+        Some(max.clone() - u256::from(51199u32))
+    );
+    assert_eq!(
+        insert("User", &"9".repeat(41), counter),
+        Some(max - u256::from(51200u32))
+    );
+    assert_eq!(Cns::resolve("User", "1.0"), Some(user));
+}
+
+#[test]
+#[should_panic(expected = "fail to register `Counter` of version `1.0` in CNS")]
+fn register_cns_twice() {
+    test_api::register_cns("Counter", "1.0", DOUBLER, "[]");
+    test_api::register_cns("Counter", "1.0", READER, "[]");
+}
+
+#[test]
+fn permission() {
+    let (account, contract) = (READER, DOUBLER);
+    assert_eq!(
+        Permission::grant_write("t_test", &account),
+        Some(i256::from(1))
+    );
+    assert_eq!(Permission::grant_deploy(&account), Some(i256::from(1)));
+    assert_eq!(
+        Permission::grant_contract_write(&contract, &account),
+        Some(i256::from(1))
+    );
+    assert!(test_api::get_write_permissions("t_test").contains(&account));
+    assert!(test_api::get_deploy_permissions().contains(&account));
+    assert!(test_api::get_contract_write_permissions(&contract).contains(&account));
+
+    assert_eq!(
+        Permission::grant_write("t_test", &account),
+        Some(i256::from(-51000))
+    );
+    assert_eq!(Permission::revoke_deploy(&account), Some(i256::from(1)));
+    assert_eq!(
+        Permission::revoke_deploy(&account),
+        Some(i256::from(-51001))
+    );
+    assert_eq!(
+        Permission::revoke_contract_write(&contract, &account),
+        Some(i256::from(1))
+    );
+    assert!(test_api::get_deploy_permissions().is_empty());
+    assert!(test_api::get_contract_write_permissions(&contract).is_empty());
+    assert!(test_api::get_write_permissions("t_test").contains(&account));
+}
+
+#[test]
+fn system_config() {
+    assert_eq!(
+        test_api::get_system_config(TX_COUNT_LIMIT).as_deref(),
+        Some("1000")
+    );
+    assert_eq!(SystemConfig::set_tx_count_limit(2000), Some(i256::from(1)));
+    assert_eq!(
+        SystemConfig::set_tx_gas_limit(99_999),
+        Some(i256::from(-51300))
+    );
+    assert_eq!(
+        SystemConfig::set_value_by_key("unknown", "1"),
+        Some(i256::from(-51300))
+    );
+    assert_eq!(
+        test_api::get_system_config(TX_COUNT_LIMIT).as_deref(),
+        Some("2000")
+    );
+    assert_eq!(
+        test_api::get_system_config(TX_GAS_LIMIT).as_deref(),
+        Some("300000000")
+    );
+    assert_eq!(test_api::get_system_config("unknown"), None);
+
+    // Configs are rolled back together with the transaction setting them.
+    let result = test_api::transact(|| {
+        SystemConfig::set_tx_count_limit(1);
+        env::revert(&String::from("oops"));
+    });
+    assert!(result.is_err());
+    assert_eq!(
+        test_api::get_system_config(TX_COUNT_LIMIT).as_deref(),
+        Some("2000")
+    );
+}
+
+#[test]
+fn consensus() {
+    let (node0, node1) = ("0".repeat(128), "a".repeat(128));
+    test_api::set_consensus_node(&node0, NodeType::Sealer);
+    assert_eq!(Consensus::add_observer(&node1), Some(i256::from(1)));
+    assert_eq!(Consensus::add_sealer("0x1234"), Some(i256::from(-51100)));
+    assert_eq!(test_api::get_consensus_nodes()[&node1], NodeType::Observer);
+
+    // The last sealer can be neither removed nor turned into an observer.
+    assert_eq!(Consensus::remove(&node0), Some(i256::from(-51101)));
+    assert_eq!(Consensus::add_observer(&node0), Some(i256::from(-51101)));
+    assert_eq!(Consensus::add_sealer(&node1), Some(i256::from(1)));
+    assert_eq!(Consensus::remove(&node0), Some(i256::from(1)));
+    assert_eq!(Consensus::remove(&node0), Some(i256::from(0)));
+
+    let nodes = test_api::get_consensus_nodes();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[&node1], NodeType::Sealer);
+}
@@ -0,0 +1,83 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::test_api;
+use crate::{lang_core::env, EnvAccess};
+use liquid_primitives::types::Address;
+
+#[test]
+fn snapshot_diff_and_restore() {
+    env::api::set_storage(b"value", &1u32);
+    env::api::set_storage(b"map$a", &1u32);
+    env::api::set_storage(b"map$b", &2u32);
+    test_api::take_snapshot("before");
+    assert!(test_api::diff_snapshot("before").is_empty());
+
+    env::api::set_storage(b"value", &2u32);
+    env::api::remove_storage(b"map$a");
+    env::api::set_storage(b"map$c", &3u32);
+    env::api::set_storage(b"map$b", &2u32);
+
+    let diff = test_api::diff_snapshot("before");
+    let address = Address::default();
+    let value = diff.field(&address, "value").unwrap();
+    assert_eq!(value.changed.len(), 1);
+    assert_eq!(value.changed[0].index, Vec::<u8>::new());
+    assert_eq!(value.changed[0].old, Some(scale::Encode::encode(&1u32)));
+    assert_eq!(value.changed[0].new, Some(scale::Encode::encode(&2u32)));
+
+    let map = diff.field(&address, "map").unwrap();
+    assert_eq!(map.removed.len(), 1);
+    assert_eq!(map.removed[0].index, b"a".to_vec());
+    assert_eq!(map.added.len(), 1);
+    assert_eq!(map.added[0].index, b"c".to_vec());
+    assert!(map.changed.is_empty());
+
+    test_api::restore_snapshot("before");
+    assert!(test_api::diff_snapshot("before").is_empty());
+    assert_eq!(env::api::get_storage::<u32>(b"value").ok(), Some(1));
+    assert_eq!(env::api::get_storage::<u32>(b"map$a").ok(), Some(1));
+}
+
+#[test]
+fn restore_blocks_and_debug_messages() {
+    test_api::advance_blocks(2);
+    env::api::debug_message(format_args!("before"));
+    test_api::take_snapshot("before");
+
+    test_api::set_next_block_number(10);
+    test_api::set_next_block_timestamp(100);
+    test_api::advance_block();
+    env::api::debug_message(format_args!("after"));
+    assert_eq!(EnvAccess {}.get_block_number(), 10);
+
+    test_api::restore_snapshot("before");
+    assert_eq!(EnvAccess {}.get_block_number(), 2);
+    assert_eq!(test_api::get_debug_messages(), ["before"]);
+
+    // The block number and the timestamp set for the next block are restored
+    // as well.
+    test_api::set_next_block_number(10);
+    test_api::take_snapshot("pending");
+    test_api::restore_snapshot("before");
+    test_api::advance_block();
+    assert_eq!(EnvAccess {}.get_block_number(), 3);
+    test_api::restore_snapshot("pending");
+    test_api::advance_block();
+    assert_eq!(EnvAccess {}.get_block_number(), 10);
+}
+
+#[test]
+#[should_panic(expected = "there is no snapshot named `missing`")]
+fn restore_missing_snapshot() {
+    test_api::restore_snapshot("missing");
+}
@@ -0,0 +1,99 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{block_number, storage_of, test_api, transfer, DOUBLER};
+use crate::lang_core::{
+    env,
+    precompiled::{KvTable, SystemConfig, TX_COUNT_LIMIT},
+};
+use std::path::PathBuf;
+
+/// Returns a path in the temporary directory which is unique to the current
+/// thread.
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "liquid-{}-{:?}.json",
+        name,
+        std::thread::current().id()
+    ))
+}
+
+#[test]
+fn dump_and_load_state() {
+    let accounts = test_api::default_accounts();
+    test_api::set_genesis_timestamp(1000);
+    test_api::advance_block();
+    test_api::set_caller_callee(accounts.alice, DOUBLER);
+    env::api::set_storage(b"value", &1u32);
+    env::api::set_storage(b"map$\x01\x02", &2u32);
+    env::api::set_storage(b"\xff\x00", &3u32);
+    test_api::pop_execution_context();
+    env::emit(transfer("alice", 1));
+    test_api::register_asset("Token", accounts.alice, true, 100, "a token");
+    test_api::set_asset_balance("Token", accounts.bob, 10);
+    test_api::register_asset("Art", accounts.alice, false, 10, "");
+    test_api::set_asset_token("Art", 1, accounts.bob, "ipfs://1");
+    KvTable::create_table("t_kv", "id", &["name"]).unwrap();
+    let table = KvTable::open_table("t_kv").unwrap();
+    let entry = table.new_entry().unwrap();
+    entry.set("name", String::from("fuji")).unwrap();
+    table.set("apple", &entry).unwrap();
+    test_api::register_cns("Counter", "1.0", DOUBLER, "[]");
+    SystemConfig::set_tx_count_limit(2000).unwrap();
+
+    let path = temp_path("state");
+    test_api::dump_state(&path).unwrap();
+    let json = std::fs::read_to_string(&path).unwrap();
+    assert!(json.contains("\"map$0x0102\""));
+    assert!(json.contains("\"0xff00\""));
+    let expected_ledgers = test_api::dump_asset_ledgers();
+    let expected_events = test_api::get_events();
+    let expected_table = test_api::get_kv_table("t_kv");
+
+    test_api::reset();
+    test_api::load_state(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(block_number(), 1);
+    assert_eq!(env::now(), 1001);
+    assert_eq!(test_api::get_events(), expected_events);
+    assert_eq!(test_api::dump_asset_ledgers(), expected_ledgers);
+    assert_eq!(storage_of::<u32>(DOUBLER, b"value"), Some(1));
+    assert_eq!(storage_of::<u32>(DOUBLER, b"map$\x01\x02"), Some(2));
+    assert_eq!(storage_of::<u32>(DOUBLER, b"\xff\x00"), Some(3));
+    assert!(expected_table.is_some());
+    assert_eq!(test_api::get_kv_table("t_kv"), expected_table);
+    // Objects opened before dumping are still usable.
+    let entry = table.get("apple").unwrap();
+    assert_eq!(entry.get::<String>("name"), Some(String::from("fuji")));
+    assert_eq!(
+        test_api::get_cns_entry("Counter", "1.0").unwrap().address,
+        DOUBLER
+    );
+    assert_eq!(
+        test_api::get_system_config(TX_COUNT_LIMIT).as_deref(),
+        Some("2000")
+    );
+}
+
+#[test]
+fn load_invalid_state() {
+    let path = temp_path("invalid-state");
+    std::fs::write(
+        &path,
+        r#"{"contracts":{"0x12":{}},"blocks":[],"events":[],"assets":{}}"#,
+    )
+    .unwrap();
+    let err = test_api::load_state(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
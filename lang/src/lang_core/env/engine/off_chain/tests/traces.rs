@@ -0,0 +1,129 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    call_data_of, doubler, forwarder, issue_token, reverter, test_api,
+    test_api::StateKey, DOUBLER, FORWARDER, REVERTER,
+};
+use crate::lang_core::env;
+use liquid_primitives::types::Address;
+
+#[test]
+fn trace_execution() {
+    test_api::register_contract(DOUBLER, doubler);
+    test_api::register_contract(REVERTER, reverter);
+    test_api::register_contract(FORWARDER, forwarder);
+    test_api::register_method_names(DOUBLER, &[([0x00; 4], "double")]);
+
+    let (output, trace) =
+        test_api::trace_execution(|| env::call::<u32>(&FORWARDER, &call_data_of(0)));
+    assert_eq!(output.ok(), Some(2));
+
+    let calls = trace.calls();
+    assert_eq!(calls.len(), 3);
+    assert_eq!(calls[0].callee, FORWARDER);
+    assert_eq!(calls[0].steps.len(), 3);
+    assert_eq!(calls[1].caller, FORWARDER);
+    assert_eq!(calls[1].callee, DOUBLER);
+    assert_eq!(calls[1].selector, Some([0x00; 4]));
+    assert!(!calls[1].reverted);
+    assert_eq!(calls[2].callee, REVERTER);
+    assert_eq!(calls[2].method, None);
+    assert!(calls[2].reverted);
+    assert_eq!(trace.calls_of("double").len(), 1);
+    assert_eq!(trace.events().len(), 1);
+    assert_eq!(trace.storage_writes().len(), 2);
+    assert_eq!(trace.revert_reason(), Some("always revert"));
+
+    let printed = trace.to_string();
+    let lines = printed.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 7);
+    assert!(lines[0].starts_with("call 0x0000"));
+    assert!(lines[1].starts_with("  call 0x3333"));
+    assert!(lines[1].ends_with("double (0x00000000)"));
+    assert_eq!(lines[2], format!("    write input = {}", "0x01000000"));
+    assert!(lines[3].starts_with("  event ["));
+    assert!(lines[4].ends_with("(0x00000000) [reverted]"));
+    assert_eq!(lines[6], "    revert \"always revert\"");
+}
+
+#[test]
+fn trace_reverted_transaction() {
+    let (result, trace) = test_api::trace_execution(|| {
+        test_api::transact(|| {
+            env::api::set_storage(b"value", &1u32);
+            env::revert(&String::from("oops"));
+        })
+    });
+    assert!(result.is_err());
+    assert_eq!(trace.storage_writes().len(), 1);
+    assert_eq!(trace.revert_reason(), Some("oops"));
+    assert!(trace.calls().is_empty());
+}
+
+#[test]
+fn trace_storage_access() {
+    let balance_of = |account: &Address| {
+        let mut key = b"balances$".to_vec();
+        key.extend(scale::Encode::encode(account));
+        key
+    };
+    let accounts = test_api::default_accounts();
+    let transfer = |from: &Address, to: &Address| {
+        test_api::trace_access(|| {
+            let _ = env::api::get_storage::<u32>(&balance_of(from));
+            env::api::set_storage(&balance_of(from), &0u32);
+            env::api::set_storage(&balance_of(to), &1u32);
+            let supply = env::api::get_storage::<u32>(b"total").unwrap_or(0);
+            env::api::set_storage(b"total", &(supply + 1));
+        })
+        .1
+    };
+
+    let first = transfer(&accounts.alice, &accounts.bob);
+    assert_eq!(first.reads.len(), 2);
+    assert_eq!(first.writes.len(), 3);
+    let key = first.reads.iter().next().unwrap();
+    assert_eq!(key.field(), Some("balances"));
+    assert_eq!(key.decode_index::<Address>(), Some(accounts.alice));
+
+    let second = transfer(&accounts.charlie, &accounts.david);
+    let conflicts = first.conflicts(&second);
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts.iter().next().unwrap().field(), Some("total"));
+
+    let serializing = test_api::serializing_keys(&[first, second]);
+    assert_eq!(
+        serializing.into_iter().collect::<Vec<_>>(),
+        vec![StateKey::storage(Address::default(), b"total")]
+    );
+}
+
+#[test]
+fn trace_asset_access() {
+    let accounts = test_api::default_accounts();
+    issue_token(accounts.alice);
+
+    let (_, access_set) = test_api::trace_access(|| {
+        assert!(env::transfer_asset(&accounts.bob, b"Token", 5, false));
+    });
+    let balance_of = |account: Address| StateKey::AssetBalance {
+        name: String::from("Token"),
+        account,
+    };
+    assert!(access_set.writes.contains(&balance_of(accounts.alice)));
+    assert!(access_set.writes.contains(&balance_of(accounts.bob)));
+    assert!(!access_set.writes.contains(&StateKey::AssetInfo {
+        name: String::from("Token")
+    }));
+    test_api::pop_execution_context();
+}
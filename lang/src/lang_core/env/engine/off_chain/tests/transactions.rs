@@ -0,0 +1,130 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    block_number, doubler, issue_token, test_api, test_api::Revert, transfer,
+    EnvInstance, DOUBLER,
+};
+use crate::lang_core::env::{self, engine::OnInstance};
+
+#[test]
+fn transact_commits() {
+    let result = test_api::transact(|| {
+        env::api::set_storage(b"value", &1u32);
+        1
+    });
+    assert_eq!(result, Ok(1));
+    assert_eq!(env::api::get_storage::<u32>(b"value").ok(), Some(1));
+}
+
+#[test]
+fn revert_rolls_back_storage() {
+    env::api::set_storage(b"value", &1u32);
+    let result = test_api::transact(|| {
+        env::api::set_storage(b"value", &2u32);
+        env::api::remove_storage(b"value");
+        env::revert(&String::from("oops"));
+    });
+    assert_eq!(
+        result,
+        Err(Revert {
+            reason: String::from("oops")
+        })
+    );
+    assert_eq!(env::api::get_storage::<u32>(b"value").ok(), Some(1));
+}
+
+#[test]
+fn inner_revert_keeps_outer_changes() {
+    env::api::set_storage(b"value", &1u32);
+    let result = test_api::transact(|| {
+        env::api::set_storage(b"value", &2u32);
+        env::api::set_storage(b"other", &2u32);
+        env::emit(transfer("alice", 2));
+        let inner = test_api::transact(|| {
+            env::api::set_storage(b"value", &3u32);
+            env::api::remove_storage(b"other");
+            env::api::set_storage(b"new", &3u32);
+            env::emit(transfer("bob", 3));
+            env::revert(&String::from("oops"));
+        });
+        assert!(inner.is_err());
+        assert_eq!(env::api::get_storage::<u32>(b"value").ok(), Some(2));
+        assert_eq!(env::api::get_storage::<u32>(b"other").ok(), Some(2));
+        assert!(env::api::get_storage::<u32>(b"new").is_err());
+        assert_eq!(test_api::get_events().len(), 1);
+    });
+    assert!(result.is_ok());
+    assert_eq!(env::api::get_storage::<u32>(b"value").ok(), Some(2));
+    assert_eq!(env::api::get_storage::<u32>(b"other").ok(), Some(2));
+    assert_eq!(test_api::get_events().len(), 1);
+}
+
+#[test]
+fn revert_rolls_back_assets() {
+    let accounts = test_api::default_accounts();
+    issue_token(accounts.alice);
+
+    let result = test_api::transact(|| {
+        assert!(env::transfer_asset(&accounts.bob, b"Token", 5, false));
+        test_api::set_caller(accounts.bob);
+        env::revert(&String::from("oops"));
+    });
+    assert!(result.is_err());
+    assert_eq!(env::get_asset_balance(&accounts.alice, b"Token"), 10);
+    assert_eq!(env::get_asset_balance(&accounts.bob, b"Token"), 0);
+    assert_eq!(env::get_caller(), accounts.alice);
+    test_api::pop_execution_context();
+}
+
+#[test]
+#[should_panic(expected = "not a revert")]
+fn transact_propagates_other_panics() {
+    let _ = test_api::transact(|| panic!("not a revert"));
+}
+
+#[test]
+fn reset() {
+    test_api::set_caller(test_api::default_accounts().alice);
+    test_api::register_contract(DOUBLER, doubler);
+    env::api::set_storage(b"value", &1u32);
+    env::emit(transfer("alice", 1));
+
+    test_api::reset();
+    assert!(!test_api::is_contract_registered(&DOUBLER));
+    assert!(env::api::get_storage::<u32>(b"value").is_err());
+    assert!(test_api::get_events().is_empty());
+    assert_eq!(block_number(), 0);
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        assert!(instance.exec_contexts.is_empty())
+    });
+}
+
+#[test]
+fn run_in_isolated_env() {
+    env::api::set_storage(b"value", &1u32);
+    let value = test_api::run_in_isolated_env(|| {
+        env::api::set_storage(b"value", &2u32);
+        env::api::get_storage::<u32>(b"value").ok()
+    });
+    assert_eq!(value, Some(2));
+    assert_eq!(env::api::get_storage::<u32>(b"value").ok(), Some(1));
+
+    let result = std::panic::catch_unwind(|| {
+        test_api::run_in_isolated_env(|| {
+            env::api::set_storage(b"value", &3u32);
+            panic!("failed in isolated environment");
+        })
+    });
+    assert!(result.is_err());
+    assert_eq!(env::api::get_storage::<u32>(b"value").ok(), Some(1));
+}
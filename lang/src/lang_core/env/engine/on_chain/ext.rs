@@ -55,6 +55,14 @@ mod sys {
 
         pub fn call(address_offset: u32, data_offset: u32, data_length: u32) -> u32;
 
+        pub fn create(
+            code_offset: u32,
+            code_length: u32,
+            data_offset: u32,
+            data_length: u32,
+            result_offset: u32,
+        ) -> u32;
+
         pub fn getReturnDataSize() -> u32;
 
         pub fn getReturnData(result_offset: u32);
@@ -261,6 +269,18 @@ pub fn call(address: &[u8], data: &[u8]) -> u32 {
     }
 }
 
+pub fn create(code: &[u8], data: &[u8], result: &mut [u8]) -> u32 {
+    unsafe {
+        sys::create(
+            code.as_ptr() as u32,
+            code.len() as u32,
+            data.as_ptr() as u32,
+            data.len() as u32,
+            result.as_mut_ptr() as u32,
+        )
+    }
+}
+
 pub fn get_return_data_size() -> u32 {
     unsafe { sys::getReturnDataSize() }
 }
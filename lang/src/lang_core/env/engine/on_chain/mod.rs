@@ -18,7 +18,7 @@ use super::OnInstance;
 use crate::env::{
     backend::Env,
     calldata::CallData,
-    error::{EnvError, Result},
+    error::{CallError, EnvError, Result},
     CallMode,
 };
 use cfg_if::cfg_if;
@@ -82,6 +82,56 @@ impl EnvInstance {
         let len = self.buffer.len();
         liquid_abi_codec::Decode::decode(&mut &self.buffer[..len]).map_err(Into::into)
     }
+
+    /// Collects the return data left by a failed cross-contract call or
+    /// contract creation.
+    fn call_error() -> CallError {
+        let return_data_size = ext::get_return_data_size() as usize;
+        let mut return_data = liquid_prelude::vec::from_elem(0u8, return_data_size);
+        if return_data_size != 0 {
+            ext::get_return_data(&mut return_data);
+        }
+        CallError::from_return_data(return_data)
+    }
+
+    /// Creates a contract with `code` via the host.
+    pub(crate) fn create_contract(code: &[u8], data: &[u8]) -> Result<Address> {
+        <Self as OnInstance>::on_instance(|instance| Env::create(instance, code, data))
+    }
+
+    cfg_if! {
+        if #[cfg(feature = "solidity-compatible")] {
+            /// Calls the contract at `addr` via the host. The host doesn't
+            /// provide static calls, so `is_static` is ignored.
+            pub(crate) fn call_contract<R>(
+                addr: &Address,
+                data: &[u8],
+                _is_static: bool,
+            ) -> Result<R>
+            where
+                R: liquid_abi_codec::Decode + liquid_abi_codec::TypeInfo,
+            {
+                <Self as OnInstance>::on_instance(|instance| {
+                    Env::call(instance, addr, data)
+                })
+            }
+        } else {
+            /// Calls the contract at `addr` via the host. The host doesn't
+            /// provide static calls, so `is_static` is ignored.
+            pub(crate) fn call_contract<R>(
+                addr: &Address,
+                data: &[u8],
+                _is_static: bool,
+            ) -> Result<R>
+            where
+                R: scale::Decode,
+            {
+                <Self as OnInstance>::on_instance(|instance| {
+                    Env::call(instance, addr, data)
+                })
+            }
+        }
+    }
 }
 
 impl Env for EnvInstance {
@@ -151,7 +201,7 @@ impl Env for EnvInstance {
             {
                 let status = ext::call(&addr.0, data);
                 if status != 0 {
-                    return Err(EnvError::FailToCallForeignContract);
+                    return Err(EnvError::FailToCallForeignContract(Self::call_error()));
                 }
                 if core::mem::size_of::<R>() == 0 {
                     // The `R` is unit type.
@@ -210,7 +260,7 @@ impl Env for EnvInstance {
             {
                 let status = ext::call(&addr.0, data);
                 if status != 0 {
-                    return Err(EnvError::FailToCallForeignContract);
+                    return Err(EnvError::FailToCallForeignContract(Self::call_error()));
                 }
                 if core::mem::size_of::<R>() == 0 {
                     // The `R` is unit type.
@@ -284,6 +334,29 @@ impl Env for EnvInstance {
     fn get_block_number(&mut self) -> u64 {
         ext::get_block_number() as u64
     }
+
+    fn get_call_data_size(&mut self) -> u32 {
+        ext::get_call_data_size()
+    }
+
+    /// The host doesn't expose the depth of the call chain.
+    fn get_call_depth(&mut self) -> Result<u32> {
+        Err(EnvError::Unsupported)
+    }
+
+    fn debug_message(&mut self, message: &str) {
+        ext::print_mem(message.as_ptr() as u32, message.len() as u32);
+    }
+
+    fn create(&mut self, code: &[u8], data: &[u8]) -> Result<Address> {
+        let mut addr = [0u8; ADDRESS_LENGTH];
+        let status = ext::create(code, data, &mut addr);
+        if status != 0 {
+            return Err(EnvError::FailToCreateContract(Self::call_error()));
+        }
+        Ok(Address::new(addr))
+    }
+
     fn get_external_code_size(&self, account: &Address) -> u32 {
         ext::get_external_code_size(&account.0)
     }
@@ -326,7 +399,7 @@ impl Env for EnvInstance {
         ext::transfer_asset(&to.0, asset_name, amount_or_id, from_self)
     }
 
-    fn get_asset_balance(&self, to: &Address, asset_name: &[u8]) -> u64 {
+    fn get_asset_balance(&mut self, to: &Address, asset_name: &[u8]) -> u64 {
         ext::get_asset_balance(&to.0, asset_name)
     }
 
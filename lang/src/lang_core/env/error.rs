@@ -10,7 +10,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use cfg_if::cfg_if;
 use derive_more::From;
+use liquid_prelude::{string::String, vec::Vec};
 
 #[derive(From)]
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -20,7 +22,119 @@ pub enum EnvError {
     NotEnoughSpace,
     UnableToReadFromStorage,
     UnableToReadCallData,
-    FailToCallForeignContract,
+    #[from(ignore)]
+    FailToCallForeignContract(CallError),
+    #[from(ignore)]
+    FailToCreateContract(CallError),
+    /// The operation is not supported by the blockchain.
+    Unsupported,
+}
+
+/// The failure of a cross-contract call or a contract creation, carrying what
+/// the callee left in its return data when it reverted.
+#[derive(Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CallError {
+    /// What caused the failure.
+    pub kind: CallErrorKind,
+    /// The raw return data of the callee.
+    pub return_data: Vec<u8>,
+    /// The revert reason decoded from `return_data`, if any.
+    pub reason: Option<String>,
+}
+
+/// The cause of a failed cross-contract call or contract creation.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum CallErrorKind {
+    /// The callee reverted, or there is no callee at the address.
+    Reverted,
+    /// The callee returned successfully, but its output can't be decoded.
+    Decode,
+    /// The operation is not supported by the blockchain.
+    Unsupported,
+    /// The environment failed for other reasons, e.g., the output of the
+    /// callee doesn't fit in the buffer.
+    Env,
+}
+
+impl Default for CallErrorKind {
+    fn default() -> Self {
+        Self::Reverted
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "solidity-compatible")] {
+        /// The selector of `Error(string)`, which prefixes the revert data of
+        /// Solidity contracts.
+        pub const REVERT_SELECTOR: liquid_primitives::Selector = {
+            let hash = liquid_primitives::hash::hash(b"Error(string)");
+            [hash[0], hash[1], hash[2], hash[3]]
+        };
+
+        fn decode_reason(return_data: &[u8]) -> Option<String> {
+            let decode = |mut data: &[u8]| -> Option<String> {
+                <String as liquid_abi_codec::Decode>::decode(&mut data).ok()
+            };
+
+            if return_data.starts_with(&REVERT_SELECTOR) {
+                if let Some(reason) = decode(&return_data[REVERT_SELECTOR.len()..]) {
+                    return Some(reason);
+                }
+            }
+            // Liquid contracts revert with a bare encoded string.
+            decode(return_data)
+        }
+    } else {
+        fn decode_reason(mut return_data: &[u8]) -> Option<String> {
+            <String as scale::Decode>::decode(&mut return_data).ok()
+        }
+    }
+}
+
+impl CallError {
+    /// Creates a call error from the raw return data of a reverted callee,
+    /// decoding the revert reason from it when possible.
+    pub fn from_return_data(return_data: Vec<u8>) -> Self {
+        let reason = if return_data.is_empty() {
+            None
+        } else {
+            decode_reason(&return_data)
+        };
+        Self {
+            kind: CallErrorKind::Reverted,
+            return_data,
+            reason,
+        }
+    }
+
+    /// Creates a call error of `kind` without return data.
+    pub fn of_kind(kind: CallErrorKind) -> Self {
+        Self {
+            kind,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<EnvError> for CallError {
+    /// Errors other than a failed call or creation happen when the callee
+    /// didn't revert, so there is no revert data to report for them, only the
+    /// kind of the error is kept.
+    fn from(error: EnvError) -> Self {
+        match error {
+            EnvError::FailToCallForeignContract(call_error)
+            | EnvError::FailToCreateContract(call_error) => call_error,
+            EnvError::ScaleDecode(_) | EnvError::AbiDecode(_) => {
+                Self::of_kind(CallErrorKind::Decode)
+            }
+            EnvError::Unsupported => Self::of_kind(CallErrorKind::Unsupported),
+            EnvError::NotEnoughSpace
+            | EnvError::UnableToReadFromStorage
+            | EnvError::UnableToReadCallData => Self::of_kind(CallErrorKind::Env),
+        }
+    }
 }
 
 /// A result of environmental operations
@@ -20,12 +20,14 @@ pub mod error;
 
 pub use self::{
     api::{
-        call, emit, finish, get_address, get_asset_balance, get_call_data, get_caller,
+        call, create, debug_message, emit, finish, get_address, get_asset_balance,
+        get_block_number, get_call_data, get_call_data_size, get_call_depth, get_caller,
         get_external_code_size, get_not_fungible_asset_ids, get_not_fungible_asset_info,
         issue_fungible_asset, issue_not_fungible_asset, now, register_asset, revert,
-        transfer_asset,
+        static_call, transfer_asset,
     },
     backend::CallMode,
+    error::{CallError, CallErrorKind},
 };
 
 #[cfg(any(feature = "std", test))]
@@ -14,40 +14,57 @@ use crate::lang_core::{
     env::call,
     precompiled::{ReturnDataWrapper, CNS_ADDRESS},
 };
+use cfg_if::cfg_if;
 use liquid_abi_codec::{Decode, Encode};
 use liquid_prelude::string::String;
-use liquid_primitives::types::{u256, Address};
+use liquid_primitives::{
+    types::{u256, Address},
+    Selector,
+};
+
+cfg_if! {
+    if #[cfg(feature = "gm")] {
+        pub(crate) const CNS_INSERT_SELECTOR: Selector = [0xb8, 0xea, 0xa0, 0x8d];
+        pub(crate) const CNS_GET_ADDRESS_SELECTOR: Selector = [0xf1, 0xa3, 0x1b, 0xfa];
+    } else {
+        pub(crate) const CNS_INSERT_SELECTOR: Selector = [0xa2, 0x16, 0x46, 0x4b];
+        pub(crate) const CNS_GET_ADDRESS_SELECTOR: Selector = [0xf8, 0x5f, 0x81, 0x26];
+    }
+}
 
+/// The contract naming service (CNS) of FISCO BCOS, which maps names and
+/// versions to contract addresses.
 pub struct Cns;
 
 impl Cns {
+    /// Registers the contract at `addr` with `name` and `version`. Returns
+    /// the number of registered contracts, or a negative error code in two's
+    /// complement if `name` and `version` have been registered.
     pub fn insert(
         name: String,
         version: String,
         addr: String,
         abi: String,
     ) -> Option<u256> {
-        let mut input_data = if cfg!(feature = "gm") {
-            [0xb8, 0xea, 0xa0, 0x8d]
-        } else {
-            [0xa2, 0x16, 0x46, 0x4b]
-        }
-        .to_vec();
-
+        let mut input_data = CNS_INSERT_SELECTOR.to_vec();
         input_data.extend(&(name, version, addr, abi).encode());
         let ret = call::<ReturnDataWrapper>(&CNS_ADDRESS, &input_data).ok()?;
         <u256 as Decode>::decode(&mut ret.data.as_slice()).ok()
     }
 
+    /// Returns the address of the contract registered with `name` and
+    /// `version`, which is the zero address if there is no such contract.
     pub fn get_contract_address(name: String, version: String) -> Option<Address> {
-        let mut input_data = if cfg!(feature = "gm") {
-            [0xf1, 0xa3, 0x1b, 0xfa]
-        } else {
-            [0xf8, 0x5f, 0x81, 0x26]
-        }
-        .to_vec();
+        let mut input_data = CNS_GET_ADDRESS_SELECTOR.to_vec();
         input_data.extend(&(name, version).encode());
         let ret = call::<ReturnDataWrapper>(&CNS_ADDRESS, &input_data).ok()?;
         <Address as Decode>::decode(&mut ret.data.as_slice()).ok()
     }
+
+    /// Returns the address of the contract registered with `name` and
+    /// `version`, or `None` if there is no such contract.
+    pub fn resolve(name: &str, version: &str) -> Option<Address> {
+        Self::get_contract_address(String::from(name), String::from(version))
+            .filter(|addr| *addr != Address::default())
+    }
 }
@@ -0,0 +1,67 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lang_core::precompiled::{entry::call_object, CONSENSUS_ADDRESS};
+use cfg_if::cfg_if;
+use liquid_prelude::string::String;
+use liquid_primitives::{types::i256, Selector};
+
+cfg_if! {
+    if #[cfg(feature = "gm")] {
+        pub(crate) const ADD_SEALER_SELECTOR: Selector = [0xdf, 0x43, 0x4a, 0xcc];
+        pub(crate) const ADD_OBSERVER_SELECTOR: Selector = [0x25, 0xe8, 0x5d, 0x16];
+        pub(crate) const CONSENSUS_REMOVE_SELECTOR: Selector = [0x86, 0xb7, 0x33, 0xf9];
+    } else {
+        pub(crate) const ADD_SEALER_SELECTOR: Selector = [0x89, 0x15, 0x2d, 0x1f];
+        pub(crate) const ADD_OBSERVER_SELECTOR: Selector = [0x28, 0x00, 0xef, 0xc0];
+        pub(crate) const CONSENSUS_REMOVE_SELECTOR: Selector = [0x80, 0x59, 0x9e, 0x4b];
+    }
+}
+
+/// The consensus precompiled contract of FISCO BCOS, which manages the
+/// sealers and observers of the group. Nodes are identified by their node
+/// IDs, which are public keys in 128 hex digits.
+///
+/// All functions return the number of affected nodes, or a negative error
+/// code if the node ID is invalid or the last sealer would be removed. `None`
+/// is returned if the precompiled contract can't be called.
+pub struct Consensus;
+
+impl Consensus {
+    /// Adds the node `node_id` as a sealer, which takes part in consensus.
+    pub fn add_sealer(node_id: &str) -> Option<i256> {
+        call_object(
+            &CONSENSUS_ADDRESS,
+            ADD_SEALER_SELECTOR,
+            String::from(node_id),
+        )
+    }
+
+    /// Adds the node `node_id` as an observer, which synchronizes blocks but
+    /// doesn't take part in consensus.
+    pub fn add_observer(node_id: &str) -> Option<i256> {
+        call_object(
+            &CONSENSUS_ADDRESS,
+            ADD_OBSERVER_SELECTOR,
+            String::from(node_id),
+        )
+    }
+
+    /// Removes the node `node_id` from the group.
+    pub fn remove(node_id: &str) -> Option<i256> {
+        call_object(
+            &CONSENSUS_ADDRESS,
+            CONSENSUS_REMOVE_SELECTOR,
+            String::from(node_id),
+        )
+    }
+}
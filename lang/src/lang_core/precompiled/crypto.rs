@@ -0,0 +1,115 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lang_core::{
+    env::call,
+    precompiled::{ReturnDataWrapper, CRYPTO_ADDRESS, ECRECOVER_ADDRESS, SHA256_ADDRESS},
+};
+use cfg_if::cfg_if;
+use liquid_abi_codec::{Decode, Encode};
+use liquid_prelude::vec::Vec;
+use liquid_primitives::{
+    types::{address::*, Bytes, Bytes32},
+    Selector,
+};
+
+cfg_if! {
+    if #[cfg(feature = "gm")] {
+        pub(crate) const KECCAK256_SELECTOR: Selector = [0xb6, 0x51, 0x01, 0x07];
+        pub(crate) const SM3_SELECTOR: Selector = [0xf2, 0x56, 0x11, 0xb5];
+        pub(crate) const SM2_VERIFY_SELECTOR: Selector = [0x22, 0xed, 0xe6, 0x1e];
+    } else {
+        pub(crate) const KECCAK256_SELECTOR: Selector = [0xeb, 0x90, 0xf4, 0x59];
+        pub(crate) const SM3_SELECTOR: Selector = [0xfb, 0x34, 0x36, 0x3c];
+        pub(crate) const SM2_VERIFY_SELECTOR: Selector = [0xcb, 0xdb, 0x3a, 0x67];
+    }
+}
+
+/// The cryptographic functions provided by the blockchain.
+///
+/// Hashing and SM2 verification are served by the crypto precompiled contract
+/// of FISCO BCOS, while SHA-256 and `ecrecover` are served by the precompiled
+/// contracts inherited from Ethereum. All functions return `None` if the
+/// precompiled contract can't be called.
+pub struct Crypto;
+
+impl Crypto {
+    fn hash(selector: Selector, data: &[u8]) -> Option<Bytes32> {
+        let mut input_data = selector.to_vec();
+        input_data.extend(&Bytes::from(data).encode());
+        let ret = call::<ReturnDataWrapper>(&CRYPTO_ADDRESS, &input_data).ok()?;
+        <Bytes32 as Decode>::decode(&mut ret.data.as_slice()).ok()
+    }
+
+    /// Computes the Keccak-256 hash of `data`.
+    pub fn keccak256(data: &[u8]) -> Option<Bytes32> {
+        Self::hash(KECCAK256_SELECTOR, data)
+    }
+
+    /// Computes the SM3 hash of `data`.
+    pub fn sm3(data: &[u8]) -> Option<Bytes32> {
+        Self::hash(SM3_SELECTOR, data)
+    }
+
+    /// Computes the SHA-256 hash of `data`.
+    pub fn sha256(data: &[u8]) -> Option<Bytes32> {
+        let ret = call::<ReturnDataWrapper>(&SHA256_ADDRESS, data).ok()?;
+        if ret.data.len() != Bytes32::LEN {
+            return None;
+        }
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&ret.data);
+        Some(Bytes32(hash))
+    }
+
+    /// Recovers the address of the account which signed `hash` with a
+    /// secp256k1 signature `(v, r, s)`, where `v` is 27 or 28. Returns `None`
+    /// if the signature is invalid.
+    pub fn ecrecover(hash: &Bytes32, v: u8, r: &Bytes32, s: &Bytes32) -> Option<Address> {
+        let mut input_data = Vec::with_capacity(128);
+        input_data.extend_from_slice(&hash.0);
+        input_data.extend_from_slice(&[0u8; 31]);
+        input_data.push(v);
+        input_data.extend_from_slice(&r.0);
+        input_data.extend_from_slice(&s.0);
+        let ret = call::<ReturnDataWrapper>(&ECRECOVER_ADDRESS, &input_data).ok()?;
+        // The address is left-padded to 32 bytes, and the output is empty if
+        // the signature is invalid.
+        if ret.data.len() != 32 {
+            return None;
+        }
+        let mut address = [0u8; ADDRESS_LENGTH];
+        address.copy_from_slice(&ret.data[32 - ADDRESS_LENGTH..]);
+        Some(Address::new(address))
+    }
+
+    /// Verifies the SM2 signature `(r, s)` of `hash` against `public_key`,
+    /// which is the uncompressed public key without the `0x04` prefix. Returns
+    /// the address of the signer if the signature is valid.
+    pub fn sm2_verify(
+        hash: &Bytes32,
+        public_key: &[u8],
+        r: &Bytes32,
+        s: &Bytes32,
+    ) -> Option<Address> {
+        let mut input_data = SM2_VERIFY_SELECTOR.to_vec();
+        input_data.extend(&(*hash, Bytes::from(public_key), *r, *s).encode());
+        let ret = call::<ReturnDataWrapper>(&CRYPTO_ADDRESS, &input_data).ok()?;
+        let (valid, address) =
+            <(bool, Address) as Decode>::decode(&mut ret.data.as_slice()).ok()?;
+        if valid {
+            Some(address)
+        } else {
+            None
+        }
+    }
+}
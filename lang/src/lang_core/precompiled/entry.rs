@@ -0,0 +1,116 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lang_core::{env::call, precompiled::ReturnDataWrapper};
+use cfg_if::cfg_if;
+use liquid_abi_codec::{Decode, Encode, MediateDecode, MediateEncode};
+use liquid_prelude::string::String;
+use liquid_primitives::{
+    types::{i256, u256, Address},
+    Selector,
+};
+
+cfg_if! {
+    if #[cfg(feature = "gm")] {
+        pub(crate) const GET_INT_SELECTOR: Selector = [0x49, 0x00, 0x86, 0x2e];
+        pub(crate) const GET_UINT_SELECTOR: Selector = [0xdf, 0x74, 0x27, 0xaf];
+        pub(crate) const GET_ADDRESS_SELECTOR: Selector = [0x07, 0xaf, 0xbf, 0x3a];
+        pub(crate) const GET_STRING_SELECTOR: Selector = [0x9b, 0xca, 0x41, 0xe8];
+        pub(crate) const SET_INT_SELECTOR: Selector = [0xde, 0xf4, 0x26, 0x98];
+        pub(crate) const SET_UINT_SELECTOR: Selector = [0xf2, 0xf4, 0xee, 0x6d];
+        pub(crate) const SET_ADDRESS_SELECTOR: Selector = [0x51, 0x7c, 0x4d, 0xd9];
+        pub(crate) const SET_STRING_SELECTOR: Selector = [0x1a, 0x39, 0x1c, 0xb4];
+    } else {
+        pub(crate) const GET_INT_SELECTOR: Selector = [0xfd, 0xa6, 0x9f, 0xae];
+        pub(crate) const GET_UINT_SELECTOR: Selector = [0x35, 0x36, 0x04, 0x6a];
+        pub(crate) const GET_ADDRESS_SELECTOR: Selector = [0xbf, 0x40, 0xfa, 0xc1];
+        pub(crate) const GET_STRING_SELECTOR: Selector = [0x9c, 0x98, 0x1f, 0xcb];
+        pub(crate) const SET_INT_SELECTOR: Selector = [0x2e, 0xf8, 0xba, 0x74];
+        pub(crate) const SET_UINT_SELECTOR: Selector = [0x8a, 0x42, 0xeb, 0xe9];
+        pub(crate) const SET_ADDRESS_SELECTOR: Selector = [0xa8, 0x15, 0xff, 0x15];
+        pub(crate) const SET_STRING_SELECTOR: Selector = [0xe9, 0x42, 0xb5, 0x16];
+    }
+}
+
+/// Calls the precompiled object at `address` and decodes its output as `R`.
+pub(super) fn call_object<A, R>(
+    address: &Address,
+    selector: Selector,
+    args: A,
+) -> Option<R>
+where
+    A: Encode,
+    R: Decode,
+{
+    let mut input_data = selector.to_vec();
+    input_data.extend(&args.encode());
+    let ret = call::<ReturnDataWrapper>(address, &input_data).ok()?;
+    <R as Decode>::decode(&mut ret.data.as_slice()).ok()
+}
+
+/// The types of values which can be stored in the fields of an [`Entry`].
+pub trait EntryValue: MediateEncode + MediateDecode + private::Sealed {
+    #[doc(hidden)]
+    const GET_SELECTOR: Selector;
+    #[doc(hidden)]
+    const SET_SELECTOR: Selector;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_entry_value {
+    ($($ty:ty => ($get:ident, $set:ident)),* $(,)?) => {
+        $(
+            impl private::Sealed for $ty {}
+
+            impl EntryValue for $ty {
+                const GET_SELECTOR: Selector = $get;
+                const SET_SELECTOR: Selector = $set;
+            }
+        )*
+    };
+}
+
+impl_entry_value! {
+    i256 => (GET_INT_SELECTOR, SET_INT_SELECTOR),
+    u256 => (GET_UINT_SELECTOR, SET_UINT_SELECTOR),
+    Address => (GET_ADDRESS_SELECTOR, SET_ADDRESS_SELECTOR),
+    String => (GET_STRING_SELECTOR, SET_STRING_SELECTOR),
+}
+
+/// An entry of a table, i.e., a row whose fields are set one by one.
+///
+/// Entries live in the blockchain rather than in the contract, an entry can
+/// be obtained from a [`KvTable`](super::KvTable) or a [`Table`](super::Table).
+pub struct Entry {
+    pub(super) address: Address,
+}
+
+impl Entry {
+    /// Returns the value of `field`, or `None` if the entry can't be read.
+    pub fn get<V: EntryValue>(&self, field: &str) -> Option<V> {
+        call_object(&self.address, V::GET_SELECTOR, String::from(field))
+    }
+
+    /// Sets `field` to `value`, and returns the entry itself so that fields
+    /// can be set in a chain. Returns `None` if the entry can't be written.
+    pub fn set<V: EntryValue>(&self, field: &str, value: V) -> Option<&Self> {
+        call_object::<_, ()>(
+            &self.address,
+            V::SET_SELECTOR,
+            (String::from(field), value),
+        )?;
+        Some(self)
+    }
+}
@@ -0,0 +1,105 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lang_core::precompiled::{
+    entry::{call_object, Entry},
+    KV_TABLE_FACTORY_ADDRESS,
+};
+use cfg_if::cfg_if;
+use liquid_prelude::string::String;
+use liquid_primitives::{
+    types::{i256, Address},
+    Selector,
+};
+
+cfg_if! {
+    if #[cfg(feature = "gm")] {
+        pub(crate) const OPEN_TABLE_SELECTOR: Selector = [0x59, 0xa4, 0x8b, 0x65];
+        pub(crate) const CREATE_TABLE_SELECTOR: Selector = [0xc9, 0x2a, 0x78, 0x01];
+        pub(crate) const GET_SELECTOR: Selector = [0x7b, 0x1b, 0x8e, 0x03];
+        pub(crate) const SET_SELECTOR: Selector = [0x51, 0x7c, 0x4d, 0xd9];
+        pub(crate) const NEW_ENTRY_SELECTOR: Selector = [0x58, 0x87, 0xab, 0x24];
+    } else {
+        pub(crate) const OPEN_TABLE_SELECTOR: Selector = [0xf2, 0x3f, 0x63, 0xc9];
+        pub(crate) const CREATE_TABLE_SELECTOR: Selector = [0x56, 0x00, 0x4b, 0x6a];
+        pub(crate) const GET_SELECTOR: Selector = [0x69, 0x3e, 0xc8, 0x5e];
+        pub(crate) const SET_SELECTOR: Selector = [0xa8, 0x15, 0xff, 0x15];
+        pub(crate) const NEW_ENTRY_SELECTOR: Selector = [0x13, 0xdb, 0x93, 0x46];
+    }
+}
+
+/// A KV table provided by the KV table precompiled contract of FISCO BCOS,
+/// which maps primary keys to entries.
+///
+/// All functions return `None` if the precompiled contract can't be called.
+pub struct KvTable {
+    address: Address,
+}
+
+impl KvTable {
+    /// Creates a table named `name`, with the primary key field `primary_key`
+    /// and value fields `fields`. Returns the error code, which is 0 on
+    /// success.
+    pub fn create_table(name: &str, primary_key: &str, fields: &[&str]) -> Option<i256> {
+        call_object(
+            &KV_TABLE_FACTORY_ADDRESS,
+            CREATE_TABLE_SELECTOR,
+            (
+                String::from(name),
+                String::from(primary_key),
+                fields.join(","),
+            ),
+        )
+    }
+
+    /// Opens the table named `name`, or returns `None` if there is no such
+    /// table.
+    pub fn open_table(name: &str) -> Option<Self> {
+        let address: Address = call_object(
+            &KV_TABLE_FACTORY_ADDRESS,
+            OPEN_TABLE_SELECTOR,
+            String::from(name),
+        )?;
+        if address == Address::default() {
+            return None;
+        }
+        Some(Self { address })
+    }
+
+    /// Returns the entry whose primary key is `primary_key`, or `None` if
+    /// there is no such entry.
+    pub fn get(&self, primary_key: &str) -> Option<Entry> {
+        let (found, address): (bool, Address) =
+            call_object(&self.address, GET_SELECTOR, String::from(primary_key))?;
+        if !found {
+            return None;
+        }
+        Some(Entry { address })
+    }
+
+    /// Inserts `entry` with `primary_key`, or replaces the existing one.
+    /// Returns the number of affected entries.
+    pub fn set(&self, primary_key: &str, entry: &Entry) -> Option<i256> {
+        call_object(
+            &self.address,
+            SET_SELECTOR,
+            (String::from(primary_key), entry.address),
+        )
+    }
+
+    /// Creates an empty entry, whose fields can be set before passing it to
+    /// [`KvTable::set`].
+    pub fn new_entry(&self) -> Option<Entry> {
+        let address = call_object(&self.address, NEW_ENTRY_SELECTOR, ())?;
+        Some(Entry { address })
+    }
+}
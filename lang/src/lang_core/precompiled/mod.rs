@@ -11,17 +11,103 @@
 // limitations under the License.
 
 mod cns;
+mod consensus;
+mod crypto;
+mod entry;
+mod kv_table;
+mod permission;
+mod system_config;
+mod table;
 
 use cfg_if::cfg_if;
 pub use cns::Cns;
+#[cfg(feature = "std")]
+pub(crate) use cns::{CNS_GET_ADDRESS_SELECTOR, CNS_INSERT_SELECTOR};
+pub use consensus::Consensus;
+#[cfg(feature = "std")]
+pub(crate) use consensus::{
+    ADD_OBSERVER_SELECTOR, ADD_SEALER_SELECTOR, CONSENSUS_REMOVE_SELECTOR,
+};
+pub use crypto::Crypto;
+#[cfg(feature = "std")]
+pub(crate) use crypto::{KECCAK256_SELECTOR, SM2_VERIFY_SELECTOR, SM3_SELECTOR};
+pub use entry::{Entry, EntryValue};
+#[cfg(feature = "std")]
+pub(crate) use entry::{
+    GET_ADDRESS_SELECTOR, GET_INT_SELECTOR, GET_STRING_SELECTOR, GET_UINT_SELECTOR,
+    SET_INT_SELECTOR, SET_STRING_SELECTOR, SET_UINT_SELECTOR,
+};
+pub use kv_table::KvTable;
+pub(crate) use kv_table::{
+    CREATE_TABLE_SELECTOR, NEW_ENTRY_SELECTOR, OPEN_TABLE_SELECTOR,
+};
+#[cfg(feature = "std")]
+pub(crate) use kv_table::{GET_SELECTOR, SET_SELECTOR};
 use liquid_prelude::vec::{self, Vec};
 use liquid_primitives::types::Address;
+pub use permission::{Permission, SYS_TABLES};
+#[cfg(feature = "std")]
+pub(crate) use permission::{
+    GRANT_WRITE_SELECTOR, PERMISSION_INSERT_SELECTOR, PERMISSION_REMOVE_SELECTOR,
+    REVOKE_WRITE_SELECTOR,
+};
+#[cfg(feature = "std")]
+pub(crate) use system_config::SET_VALUE_BY_KEY_SELECTOR;
+pub use system_config::{SystemConfig, TX_COUNT_LIMIT, TX_GAS_LIMIT};
+pub use table::{Condition, ConditionValue, Entries, Table};
+#[cfg(feature = "std")]
+pub(crate) use table::{
+    ENTRIES_GET_SELECTOR, ENTRIES_SIZE_SELECTOR, EQ_INT_SELECTOR, EQ_STRING_SELECTOR,
+    GE_SELECTOR, GT_SELECTOR, INSERT_SELECTOR, LE_SELECTOR, LIMIT_SELECTOR, LT_SELECTOR,
+    NEW_CONDITION_SELECTOR, NE_INT_SELECTOR, NE_STRING_SELECTOR, REMOVE_SELECTOR,
+    SELECT_SELECTOR, UPDATE_SELECTOR,
+};
+
+pub const SYSTEM_CONFIG_ADDRESS: Address = Address::new([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+]);
+
+pub const CONSENSUS_ADDRESS: Address = Address::new([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x03,
+]);
 
 pub const CNS_ADDRESS: Address = Address::new([
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     0x00, 0x00, 0x00, 0x00, 0x10, 0x04,
 ]);
 
+pub const PERMISSION_ADDRESS: Address = Address::new([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x05,
+]);
+
+pub const CRYPTO_ADDRESS: Address = Address::new([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x0a,
+]);
+
+pub const TABLE_FACTORY_ADDRESS: Address = Address::new([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x01,
+]);
+
+pub const KV_TABLE_FACTORY_ADDRESS: Address = Address::new([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x10,
+]);
+
+pub const ECRECOVER_ADDRESS: Address = Address::new([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+]);
+
+pub const SHA256_ADDRESS: Address = Address::new([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+]);
+
 struct ReturnDataWrapper {
     pub data: Vec<u8>,
 }
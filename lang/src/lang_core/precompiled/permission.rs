@@ -0,0 +1,97 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lang_core::precompiled::{entry::call_object, PERMISSION_ADDRESS};
+use cfg_if::cfg_if;
+use liquid_prelude::string::{String, ToString};
+use liquid_primitives::{
+    types::{i256, Address},
+    Selector,
+};
+
+cfg_if! {
+    if #[cfg(feature = "gm")] {
+        pub(crate) const PERMISSION_INSERT_SELECTOR: Selector = [0xce, 0x0a, 0x9f, 0xb9];
+        pub(crate) const PERMISSION_REMOVE_SELECTOR: Selector = [0x85, 0xd2, 0x3a, 0xfc];
+        pub(crate) const GRANT_WRITE_SELECTOR: Selector = [0xd0, 0x10, 0xd2, 0x3c];
+        pub(crate) const REVOKE_WRITE_SELECTOR: Selector = [0xdf, 0x12, 0xfe, 0x78];
+    } else {
+        pub(crate) const PERMISSION_INSERT_SELECTOR: Selector = [0x06, 0xe6, 0x3f, 0xf8];
+        pub(crate) const PERMISSION_REMOVE_SELECTOR: Selector = [0x44, 0x59, 0x0a, 0x7e];
+        pub(crate) const GRANT_WRITE_SELECTOR: Selector = [0x96, 0xec, 0x37, 0xc4];
+        pub(crate) const REVOKE_WRITE_SELECTOR: Selector = [0x99, 0xc2, 0x60, 0x10];
+    }
+}
+
+/// The system table whose write permission is the permission to deploy
+/// contracts and create tables.
+pub const SYS_TABLES: &str = "_sys_tables_";
+
+/// The permission precompiled contract of FISCO BCOS, which manages the
+/// accounts allowed to write tables, contracts and to deploy contracts.
+///
+/// All functions return the number of affected permissions, or a negative
+/// error code if the permission has been granted or hasn't been granted.
+/// `None` is returned if the precompiled contract can't be called.
+pub struct Permission;
+
+impl Permission {
+    /// Grants `account` the permission to write the table named `table_name`.
+    pub fn grant_write(table_name: &str, account: &Address) -> Option<i256> {
+        call_object(
+            &PERMISSION_ADDRESS,
+            PERMISSION_INSERT_SELECTOR,
+            (String::from(table_name), account.to_string()),
+        )
+    }
+
+    /// Revokes the permission of `account` to write the table named
+    /// `table_name`.
+    pub fn revoke_write(table_name: &str, account: &Address) -> Option<i256> {
+        call_object(
+            &PERMISSION_ADDRESS,
+            PERMISSION_REMOVE_SELECTOR,
+            (String::from(table_name), account.to_string()),
+        )
+    }
+
+    /// Grants `account` the permission to deploy contracts and create tables.
+    pub fn grant_deploy(account: &Address) -> Option<i256> {
+        Self::grant_write(SYS_TABLES, account)
+    }
+
+    /// Revokes the permission of `account` to deploy contracts and create
+    /// tables.
+    pub fn revoke_deploy(account: &Address) -> Option<i256> {
+        Self::revoke_write(SYS_TABLES, account)
+    }
+
+    /// Grants `account` the permission to write the storage of the contract
+    /// at `contract`.
+    pub fn grant_contract_write(contract: &Address, account: &Address) -> Option<i256> {
+        call_object(
+            &PERMISSION_ADDRESS,
+            GRANT_WRITE_SELECTOR,
+            (*contract, *account),
+        )
+    }
+
+    /// Revokes the permission of `account` to write the storage of the
+    /// contract at `contract`.
+    pub fn revoke_contract_write(contract: &Address, account: &Address) -> Option<i256> {
+        call_object(
+            &PERMISSION_ADDRESS,
+            REVOKE_WRITE_SELECTOR,
+            (*contract, *account),
+        )
+    }
+}
@@ -0,0 +1,60 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lang_core::precompiled::{entry::call_object, SYSTEM_CONFIG_ADDRESS};
+use cfg_if::cfg_if;
+use liquid_prelude::string::{String, ToString};
+use liquid_primitives::{types::i256, Selector};
+
+cfg_if! {
+    if #[cfg(feature = "gm")] {
+        pub(crate) const SET_VALUE_BY_KEY_SELECTOR: Selector = [0x07, 0x49, 0xb5, 0x18];
+    } else {
+        pub(crate) const SET_VALUE_BY_KEY_SELECTOR: Selector = [0xbd, 0x29, 0x1a, 0xef];
+    }
+}
+
+/// The key of the maximum number of transactions in a block.
+pub const TX_COUNT_LIMIT: &str = "tx_count_limit";
+
+/// The key of the maximum gas of a transaction.
+pub const TX_GAS_LIMIT: &str = "tx_gas_limit";
+
+/// The system config precompiled contract of FISCO BCOS, which sets the
+/// configurations of the chain.
+///
+/// All functions return 1 on success, or a negative error code if the value
+/// is invalid. `None` is returned if the precompiled contract can't be
+/// called.
+pub struct SystemConfig;
+
+impl SystemConfig {
+    /// Sets the configuration `key` to `value`.
+    pub fn set_value_by_key(key: &str, value: &str) -> Option<i256> {
+        call_object(
+            &SYSTEM_CONFIG_ADDRESS,
+            SET_VALUE_BY_KEY_SELECTOR,
+            (String::from(key), String::from(value)),
+        )
+    }
+
+    /// Sets the maximum number of transactions in a block, which must be at
+    /// least 1.
+    pub fn set_tx_count_limit(limit: u64) -> Option<i256> {
+        Self::set_value_by_key(TX_COUNT_LIMIT, &limit.to_string())
+    }
+
+    /// Sets the maximum gas of a transaction, which must be at least 100000.
+    pub fn set_tx_gas_limit(limit: u64) -> Option<i256> {
+        Self::set_value_by_key(TX_GAS_LIMIT, &limit.to_string())
+    }
+}
@@ -0,0 +1,413 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lang_core::precompiled::{
+    entry::{call_object, Entry},
+    CREATE_TABLE_SELECTOR, NEW_ENTRY_SELECTOR, OPEN_TABLE_SELECTOR,
+    TABLE_FACTORY_ADDRESS,
+};
+use cfg_if::cfg_if;
+use core::convert::TryFrom;
+use liquid_abi_codec::MediateEncode;
+use liquid_prelude::{string::String, vec::Vec};
+use liquid_primitives::{
+    types::{i256, Address},
+    Selector,
+};
+
+cfg_if! {
+    if #[cfg(feature = "gm")] {
+        pub(crate) const SELECT_SELECTOR: Selector = [0xd8, 0xac, 0x59, 0x57];
+        pub(crate) const INSERT_SELECTOR: Selector = [0x4c, 0x6f, 0x30, 0xc0];
+        pub(crate) const UPDATE_SELECTOR: Selector = [0x66, 0x4b, 0x37, 0xd6];
+        pub(crate) const REMOVE_SELECTOR: Selector = [0x09, 0xff, 0x42, 0xf0];
+        pub(crate) const NEW_CONDITION_SELECTOR: Selector = [0xc7, 0x4f, 0x8c, 0xaf];
+        pub(crate) const EQ_INT_SELECTOR: Selector = [0xd6, 0x2b, 0x54, 0xb4];
+        pub(crate) const EQ_STRING_SELECTOR: Selector = [0xae, 0x76, 0x3d, 0xb5];
+        pub(crate) const NE_INT_SELECTOR: Selector = [0xf9, 0x55, 0x26, 0x4b];
+        pub(crate) const NE_STRING_SELECTOR: Selector = [0x96, 0x6b, 0x08, 0x22];
+        pub(crate) const GT_SELECTOR: Selector = [0xe1, 0xf1, 0x0e, 0xe0];
+        pub(crate) const GE_SELECTOR: Selector = [0x40, 0xb5, 0xf1, 0xab];
+        pub(crate) const LT_SELECTOR: Selector = [0x1e, 0xe8, 0x87, 0x91];
+        pub(crate) const LE_SELECTOR: Selector = [0x2e, 0xc3, 0x46, 0xc9];
+        pub(crate) const LIMIT_SELECTOR: Selector = [0x32, 0x49, 0x27, 0x37];
+        pub(crate) const ENTRIES_GET_SELECTOR: Selector = [0x3d, 0xd2, 0xb6, 0x14];
+        pub(crate) const ENTRIES_SIZE_SELECTOR: Selector = [0xd3, 0xe9, 0xaf, 0x5a];
+    } else {
+        pub(crate) const SELECT_SELECTOR: Selector = [0xe8, 0x43, 0x4e, 0x39];
+        pub(crate) const INSERT_SELECTOR: Selector = [0x31, 0xaf, 0xac, 0x36];
+        pub(crate) const UPDATE_SELECTOR: Selector = [0xbf, 0x2b, 0x70, 0xa1];
+        pub(crate) const REMOVE_SELECTOR: Selector = [0x28, 0xbb, 0x21, 0x17];
+        pub(crate) const NEW_CONDITION_SELECTOR: Selector = [0x78, 0x57, 0xd7, 0xc9];
+        pub(crate) const EQ_INT_SELECTOR: Selector = [0xe4, 0x45, 0x94, 0xb9];
+        pub(crate) const EQ_STRING_SELECTOR: Selector = [0xcd, 0x30, 0xa1, 0xd1];
+        pub(crate) const NE_INT_SELECTOR: Selector = [0x39, 0xae, 0xf0, 0x24];
+        pub(crate) const NE_STRING_SELECTOR: Selector = [0x27, 0x83, 0xac, 0xf5];
+        pub(crate) const GT_SELECTOR: Selector = [0x08, 0xad, 0x63, 0x33];
+        pub(crate) const GE_SELECTOR: Selector = [0x42, 0xf8, 0xdd, 0x31];
+        pub(crate) const LT_SELECTOR: Selector = [0xc3, 0x1c, 0x9b, 0x65];
+        pub(crate) const LE_SELECTOR: Selector = [0xb6, 0xf2, 0x38, 0x57];
+        pub(crate) const LIMIT_SELECTOR: Selector = [0x7e, 0xc1, 0xcc, 0x65];
+        pub(crate) const ENTRIES_GET_SELECTOR: Selector = [0x84, 0x67, 0x19, 0xe0];
+        pub(crate) const ENTRIES_SIZE_SELECTOR: Selector = [0x94, 0x9d, 0x22, 0x5d];
+    }
+}
+
+/// The value compared with a field by `EQ` or `NE`.
+pub enum ConditionValue {
+    Int(i256),
+    String(String),
+}
+
+impl From<i256> for ConditionValue {
+    fn from(value: i256) -> Self {
+        ConditionValue::Int(value)
+    }
+}
+
+impl From<String> for ConditionValue {
+    fn from(value: String) -> Self {
+        ConditionValue::String(value)
+    }
+}
+
+impl From<&str> for ConditionValue {
+    fn from(value: &str) -> Self {
+        ConditionValue::String(String::from(value))
+    }
+}
+
+/// A single comparison of a [`Condition`].
+enum Comparison {
+    Eq(String, ConditionValue),
+    Ne(String, ConditionValue),
+    Gt(String, i256),
+    Ge(String, i256),
+    Lt(String, i256),
+    Le(String, i256),
+}
+
+/// The condition of `select`, `update` and `remove` of a [`Table`]. Entries
+/// match the condition if they satisfy all comparisons.
+///
+/// The condition is built in the contract, and is only sent to the
+/// blockchain when the table is accessed with it, e.g.:
+///
+/// ```ignore
+/// let condition = Condition::new()
+///     .eq("name", "fruit")
+///     .gt("price", 3.into())
+///     .limit(0, 10);
+/// ```
+#[derive(Default)]
+pub struct Condition {
+    comparisons: Vec<Comparison>,
+    /// The offset and the maximum number of entries.
+    limit: Option<(u32, u32)>,
+}
+
+impl Condition {
+    /// Creates a condition which matches all entries.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Requires `field` to be equal to `value`.
+    pub fn eq<V: Into<ConditionValue>>(mut self, field: &str, value: V) -> Self {
+        self.comparisons
+            .push(Comparison::Eq(String::from(field), value.into()));
+        self
+    }
+
+    /// Requires `field` to be not equal to `value`.
+    pub fn ne<V: Into<ConditionValue>>(mut self, field: &str, value: V) -> Self {
+        self.comparisons
+            .push(Comparison::Ne(String::from(field), value.into()));
+        self
+    }
+
+    /// Requires `field` to be greater than `value`.
+    pub fn gt(mut self, field: &str, value: i256) -> Self {
+        self.comparisons
+            .push(Comparison::Gt(String::from(field), value));
+        self
+    }
+
+    /// Requires `field` to be greater than or equal to `value`.
+    pub fn ge(mut self, field: &str, value: i256) -> Self {
+        self.comparisons
+            .push(Comparison::Ge(String::from(field), value));
+        self
+    }
+
+    /// Requires `field` to be less than `value`.
+    pub fn lt(mut self, field: &str, value: i256) -> Self {
+        self.comparisons
+            .push(Comparison::Lt(String::from(field), value));
+        self
+    }
+
+    /// Requires `field` to be less than or equal to `value`.
+    pub fn le(mut self, field: &str, value: i256) -> Self {
+        self.comparisons
+            .push(Comparison::Le(String::from(field), value));
+        self
+    }
+
+    /// Skips the first `offset` matched entries, and takes at most `count`
+    /// entries of the rest.
+    pub fn limit(mut self, offset: u32, count: u32) -> Self {
+        self.limit = Some((offset, count));
+        self
+    }
+
+    /// Creates the condition in the blockchain via `table`, and returns the
+    /// address of it.
+    fn create(&self, table: &Table) -> Option<Address> {
+        fn compare<V>(
+            address: &Address,
+            selector: Selector,
+            field: &str,
+            value: &V,
+        ) -> Option<()>
+        where
+            V: MediateEncode + Clone,
+        {
+            call_object(address, selector, (String::from(field), value.clone()))
+        }
+
+        let address = call_object(&table.address, NEW_CONDITION_SELECTOR, ())?;
+        for comparison in &self.comparisons {
+            match comparison {
+                Comparison::Eq(field, ConditionValue::Int(value)) => {
+                    compare(&address, EQ_INT_SELECTOR, field, value)
+                }
+                Comparison::Eq(field, ConditionValue::String(value)) => {
+                    compare(&address, EQ_STRING_SELECTOR, field, value)
+                }
+                Comparison::Ne(field, ConditionValue::Int(value)) => {
+                    compare(&address, NE_INT_SELECTOR, field, value)
+                }
+                Comparison::Ne(field, ConditionValue::String(value)) => {
+                    compare(&address, NE_STRING_SELECTOR, field, value)
+                }
+                Comparison::Gt(field, value) => {
+                    compare(&address, GT_SELECTOR, field, value)
+                }
+                Comparison::Ge(field, value) => {
+                    compare(&address, GE_SELECTOR, field, value)
+                }
+                Comparison::Lt(field, value) => {
+                    compare(&address, LT_SELECTOR, field, value)
+                }
+                Comparison::Le(field, value) => {
+                    compare(&address, LE_SELECTOR, field, value)
+                }
+            }?;
+        }
+        if let Some((offset, count)) = self.limit {
+            call_object::<_, ()>(
+                &address,
+                LIMIT_SELECTOR,
+                (i256::from(offset), i256::from(count)),
+            )?;
+        }
+        Some(address)
+    }
+}
+
+/// Converts `value` to `usize`, or returns `None` if it's negative or too
+/// large.
+fn to_usize(value: &i256) -> Option<usize> {
+    if *value < 0.into() {
+        return None;
+    }
+    let bytes = value.to_be_bytes();
+    if bytes[..24].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&bytes[24..]);
+    usize::try_from(u64::from_be_bytes(low)).ok()
+}
+
+/// The entries selected from a [`Table`].
+pub struct Entries {
+    address: Address,
+    len: usize,
+}
+
+impl Entries {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the entry at `index`, or `None` if out of bounds.
+    pub fn get(&self, index: usize) -> Option<Entry> {
+        if index >= self.len {
+            return None;
+        }
+        let address =
+            call_object(&self.address, ENTRIES_GET_SELECTOR, i256::from(index))?;
+        Some(Entry { address })
+    }
+
+    /// Iterates over the entries, the iteration stops early if an entry can't
+    /// be read.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            entries: self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over [`Entries`].
+pub struct Iter<'a> {
+    entries: &'a Entries,
+    index: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.entries.get(self.index)?;
+        self.index += 1;
+        Some(entry)
+    }
+}
+
+impl<'a> IntoIterator for &'a Entries {
+    type Item = Entry;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A table provided by the CRUD precompiled contract of FISCO BCOS. Unlike
+/// KV tables, a key may be shared by multiple entries, which are filtered by
+/// [`Condition`]s.
+///
+/// All functions return `None` if the precompiled contract can't be called.
+pub struct Table {
+    address: Address,
+}
+
+impl Table {
+    /// Creates a table named `name`, with the key field `key_field` and value
+    /// fields `fields`. Returns the error code, which is 0 on success.
+    pub fn create_table(name: &str, key_field: &str, fields: &[&str]) -> Option<i256> {
+        call_object(
+            &TABLE_FACTORY_ADDRESS,
+            CREATE_TABLE_SELECTOR,
+            (
+                String::from(name),
+                String::from(key_field),
+                fields.join(","),
+            ),
+        )
+    }
+
+    /// Opens the table named `name`, or returns `None` if there is no such
+    /// table.
+    pub fn open_table(name: &str) -> Option<Self> {
+        let address: Address = call_object(
+            &TABLE_FACTORY_ADDRESS,
+            OPEN_TABLE_SELECTOR,
+            String::from(name),
+        )?;
+        if address == Address::default() {
+            return None;
+        }
+        Some(Self { address })
+    }
+
+    /// Creates an empty entry, whose fields can be set before passing it to
+    /// [`Table::insert`] or [`Table::update`].
+    pub fn new_entry(&self) -> Option<Entry> {
+        let address = call_object(&self.address, NEW_ENTRY_SELECTOR, ())?;
+        Some(Entry { address })
+    }
+
+    /// Returns the entries with `key` which match `condition`.
+    pub fn select(&self, key: &str, condition: &Condition) -> Option<Entries> {
+        let condition = condition.create(self)?;
+        let address = call_object(
+            &self.address,
+            SELECT_SELECTOR,
+            (String::from(key), condition),
+        )?;
+        let len: i256 = call_object(&address, ENTRIES_SIZE_SELECTOR, ())?;
+        Some(Entries {
+            address,
+            len: to_usize(&len)?,
+        })
+    }
+
+    /// Inserts `entry` with `key`, and returns the number of inserted entries.
+    pub fn insert(&self, key: &str, entry: &Entry) -> Option<i256> {
+        call_object(
+            &self.address,
+            INSERT_SELECTOR,
+            (String::from(key), entry.address),
+        )
+    }
+
+    /// Sets the fields of the entries with `key` which match `condition` to
+    /// the ones of `entry`, and returns the number of updated entries.
+    pub fn update(
+        &self,
+        key: &str,
+        entry: &Entry,
+        condition: &Condition,
+    ) -> Option<i256> {
+        let condition = condition.create(self)?;
+        call_object(
+            &self.address,
+            UPDATE_SELECTOR,
+            (String::from(key), entry.address, condition),
+        )
+    }
+
+    /// Removes the entries with `key` which match `condition`, and returns the
+    /// number of removed entries.
+    pub fn remove(&self, key: &str, condition: &Condition) -> Option<i256> {
+        let condition = condition.create(self)?;
+        call_object(
+            &self.address,
+            REMOVE_SELECTOR,
+            (String::from(key), condition),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_to_usize() {
+        assert_eq!(to_usize(&i256::from(0)), Some(0));
+        assert_eq!(to_usize(&i256::from(42)), Some(42));
+        assert_eq!(to_usize(&i256::from(u64::MAX)), Some(u64::MAX as usize));
+        assert_eq!(to_usize(&i256::from(u128::MAX)), None);
+        assert_eq!(to_usize(&i256::from(-1)), None);
+        assert_eq!(to_usize(&i256::from(i64::MIN)), None);
+    }
+}
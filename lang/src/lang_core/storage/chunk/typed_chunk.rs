@@ -49,6 +49,7 @@ impl<T> TypedChunk<T> {
     pub fn remove<Q: AsRef<[u8]>>(&mut self, index: Q) {
         self.prepare_inner_key(index);
         env::api::remove_storage(self.key_buf.borrow().as_slice());
+        self.key_buf.borrow_mut().truncate(self.prefix_len);
     }
 }
 
@@ -114,4 +115,16 @@ mod tests {
             assert_eq!(chunk.load(TEST_KEYS[i]), Some(i as u32));
         }
     }
+
+    #[test]
+    fn remove_keeps_other_keys() {
+        let mut chunk = dummy_chunk();
+        chunk.store(b"Alice", &1);
+        chunk.store(b"Bob", &2);
+
+        chunk.remove(b"Alice");
+        assert_eq!(chunk.load(b"Alice"), None);
+        assert_eq!(chunk.load(b"Bob"), Some(2));
+        assert_eq!(chunk.get_inner_key(b"Bob"), b"var$Bob".to_vec());
+    }
 }
@@ -12,8 +12,10 @@
 
 mod iterable_mapping;
 mod mapping;
+mod sorted_mapping;
 mod vec;
 
 pub use iterable_mapping::IterableMapping;
 pub use mapping::Mapping;
+pub use sorted_mapping::SortedMapping;
 pub use vec::Vec;
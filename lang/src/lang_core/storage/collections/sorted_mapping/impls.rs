@@ -0,0 +1,546 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::lang_core::storage::{
+    Bind, CachedCell, CachedChunk, Flush,
+    You_Should_Use_A_Container_To_Wrap_Your_State_Field_In_Storage,
+};
+use cfg_if::cfg_if;
+use core::{
+    borrow::Borrow,
+    ops::{Bound, RangeBounds},
+};
+use liquid_prelude::vec::Vec;
+use liquid_primitives::hash::hash;
+use scale::{Codec, Decode, Encode};
+
+/// The maximum number of levels of the skip list.
+const MAX_LEVEL: usize = 16;
+
+#[derive(Decode, Encode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+struct Node<K, V> {
+    val: V,
+    /// The keys of the next nodes at each level of this node.
+    next: Vec<Option<K>>,
+}
+
+/// A mapping which keeps its keys in ascending order, implemented as a skip
+/// list whose nodes are stored in a chunk indexed by their keys.
+///
+/// Looking up a key costs a single storage access, while inserting, removing
+/// and searching keys by order cost O(log n) storage accesses on average.
+///
+/// # Note
+///
+/// The levels of nodes are derived from their keys and the bind key of the
+/// mapping rather than from randomness, so anyone who can choose the keys to
+/// be inserted is able to find keys sharing the same level and make these
+/// operations cost up to O(n) storage accesses.
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct SortedMapping<K, V> {
+    len: CachedCell<u32>,
+    /// The keys of the first nodes at each level.
+    head: CachedCell<Vec<Option<K>>>,
+    nodes: CachedChunk<Node<K, V>>,
+    /// The salt mixed into the hash of each key when deciding its level, so
+    /// that keys get different levels in different mappings.
+    salt: Vec<u8>,
+}
+
+/// An iterator over a range of entries of a [`SortedMapping`], in ascending
+/// order of keys.
+pub struct Range<'a, K, V> {
+    sorted_mapping: &'a SortedMapping<K, V>,
+    front: Option<&'a K>,
+    back: Option<&'a K>,
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V>
+where
+    K: Codec + Ord,
+    V: Codec,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.front?;
+        if Some(key) == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = self.sorted_mapping.next_of(Some(key));
+        }
+        Some((key, &self.sorted_mapping.node(key).val))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V>
+where
+    K: Codec + Ord,
+    V: Codec,
+{
+    /// Each step searches the previous key from the head, which costs
+    /// O(log n) storage accesses on average.
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.back?;
+        if Some(key) == self.front {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = self.sorted_mapping.last_where(|k| k < key);
+        }
+        Some((key, &self.sorted_mapping.node(key).val))
+    }
+}
+
+impl<K, V> Bind for SortedMapping<K, V> {
+    fn bind_with(key: &[u8]) -> Self {
+        let mut head_bind_key = key.to_vec();
+        head_bind_key.extend_from_slice(b"$head");
+        let mut nodes_bind_key = key.to_vec();
+        nodes_bind_key.extend_from_slice(b"$nodes");
+
+        Self {
+            len: CachedCell::<u32>::new(key),
+            head: CachedCell::<Vec<Option<K>>>::new(&head_bind_key),
+            nodes: CachedChunk::<Node<K, V>>::new(&nodes_bind_key),
+            salt: key.to_vec(),
+        }
+    }
+}
+
+impl<K, V> Flush for SortedMapping<K, V>
+where
+    K: Encode,
+    V: Encode,
+{
+    fn flush(&mut self) {
+        self.len.flush();
+        self.head.flush();
+        self.nodes.flush();
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "contract")] {
+        use crate::lang_core::storage::Getter;
+
+        macro_rules! getter_impl {
+            () => {
+                type Index = K;
+                type Output = V;
+
+                fn getter_impl(&self, index: Self::Index) -> Self::Output {
+                    self.get(&index)
+                        .expect(
+                            "[liquid_lang::SortedMapping::getter] Error: expected `index` to \
+                             be existed",
+                        )
+                        .clone()
+                }
+            };
+        }
+
+        #[cfg(feature = "solidity-compatible")]
+        impl<K, V> Getter for SortedMapping<K, V>
+        where
+            K: Codec + Ord + liquid_abi_codec::Decode,
+            V: Codec + liquid_abi_codec::Encode + Clone,
+        {
+            getter_impl!();
+        }
+
+        #[cfg(not(feature = "solidity-compatible"))]
+        impl<K, V> Getter for SortedMapping<K, V>
+        where
+            K: Codec + Ord,
+            V: Codec + Clone,
+        {
+            getter_impl!();
+        }
+    }
+}
+
+impl<K, V> SortedMapping<K, V>
+where
+    K: Codec,
+    V: Codec,
+{
+    pub fn initialize(&mut self) {
+        if self.len.get().is_none() {
+            self.len.set(0);
+            self.head.set(Vec::new());
+        }
+    }
+
+    pub fn len(&self) -> u32 {
+        *self.len.get().expect(
+            "[liquid_lang::SortedMapping::len] Error: expected `len` field to be \
+             existed in storage",
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn head(&self) -> &Vec<Option<K>> {
+        self.head.get().expect(
+            "[liquid_lang::SortedMapping::head] Error: expected `head` field to be \
+             existed in storage",
+        )
+    }
+
+    fn node(&self, key: &K) -> &Node<K, V> {
+        self.nodes.get(&key.encode()).expect(
+            "[liquid_lang::SortedMapping::node] Error: expected the node to be existed \
+             in storage",
+        )
+    }
+
+    /// Returns the next keys at each level of the node of `key`, or of the head
+    /// if `key` is `None`.
+    fn next_keys_mut(&mut self, key: Option<&K>) -> &mut Vec<Option<K>> {
+        match key {
+            None => self.head.get_mut().expect(
+                "[liquid_lang::SortedMapping::next_keys_mut] Error: expected `head` \
+                 field to be existed in storage",
+            ),
+            Some(key) => {
+                &mut self
+                    .nodes
+                    .get_mut(&key.encode())
+                    .expect(
+                        "[liquid_lang::SortedMapping::next_keys_mut] Error: expected \
+                         the node to be existed in storage",
+                    )
+                    .next
+            }
+        }
+    }
+
+    /// Returns the key following `key` at the lowest level, where `None` stands
+    /// for the head.
+    fn next_of(&self, key: Option<&K>) -> Option<&K> {
+        let next = match key {
+            None => self.head(),
+            Some(key) => &self.node(key).next,
+        };
+        next.first().and_then(Option::as_ref)
+    }
+
+    /// Returns the last key satisfying `pred`, which must be satisfied by all
+    /// keys less than any key satisfying it.
+    fn last_where<F>(&self, pred: F) -> Option<&K>
+    where
+        F: Fn(&K) -> bool,
+    {
+        self.path_where(pred).into_iter().next().flatten()
+    }
+
+    /// Returns the last keys satisfying `pred` at each level, from the lowest
+    /// one to the highest one, where `None` stands for the head.
+    fn path_where<F>(&self, pred: F) -> Vec<Option<&K>>
+    where
+        F: Fn(&K) -> bool,
+    {
+        let head = self.head();
+        let mut path = Vec::with_capacity(head.len());
+        path.resize(head.len(), None);
+
+        let mut current = None;
+        for level in (0..head.len()).rev() {
+            loop {
+                let next = match current {
+                    None => head[level].as_ref(),
+                    Some(key) => self.node(key).next[level].as_ref(),
+                };
+                match next {
+                    Some(next) if pred(next) => current = Some(next),
+                    _ => break,
+                }
+            }
+            path[level] = current;
+        }
+        path
+    }
+
+    /// Returns the level of the node of `key`, which is derived from the hash
+    /// of the salt followed by the encoded key, so that each node has a chance
+    /// of 1/4 to be raised to the next level.
+    pub(super) fn level_of(&self, encoded_key: &[u8]) -> usize {
+        let mut salted_key = self.salt.clone();
+        salted_key.extend_from_slice(encoded_key);
+        let hash = hash(&salted_key);
+        let bits = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]);
+        core::cmp::min(bits.leading_zeros() as usize / 2 + 1, MAX_LEVEL)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Encode,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Encode,
+    {
+        self.nodes.get(&key.encode()).map(|node| &node.val)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Encode,
+    {
+        self.nodes.get_mut(&key.encode()).map(|node| &mut node.val)
+    }
+
+    pub fn mutate_with<Q, F>(&mut self, key: &Q, f: F) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Encode,
+        F: FnOnce(&mut V),
+    {
+        self.nodes
+            .mutate_with(&key.encode(), |node| f(&mut node.val))
+            .map(|node| &node.val)
+    }
+}
+
+impl<K, V> SortedMapping<K, V>
+where
+    K: Codec + Ord + Clone,
+    V: Codec,
+{
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, `None` is returned.
+    ///
+    /// If the map did have this key present, the value is updated,
+    /// and the old value is returned.
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+        if let Some(old_val) = self.get_mut(&key) {
+            return Some(core::mem::replace(old_val, val));
+        }
+
+        if self.len() == u32::MAX {
+            panic!(
+                "[liquid_lang::SortedMapping::insert] Error: cannot insert more \
+                 elements than `u32::MAX`"
+            );
+        }
+
+        let encoded_key = key.encode();
+        let level = self.level_of(&encoded_key);
+        let head = self.next_keys_mut(None);
+        if head.len() < level {
+            head.resize(level, None);
+        }
+
+        let path = self
+            .path_where(|k| *k < key)
+            .into_iter()
+            .take(level)
+            .map(|prev| prev.cloned())
+            .collect::<Vec<_>>();
+        let mut next = Vec::with_capacity(level);
+        for (level, prev) in path.iter().enumerate() {
+            let prev_next = &mut self.next_keys_mut(prev.as_ref())[level];
+            next.push(prev_next.replace(key.clone()));
+        }
+        self.nodes.set(&encoded_key, Node { val, next });
+
+        let len = self.len.get_mut().expect(
+            "[liquid_lang::SortedMapping::insert] Error: expected `len` field to be \
+             existed in storage",
+        );
+        *len += 1;
+        None
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key was previously in the map.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Encode + Ord,
+    {
+        let encoded_key = key.encode();
+        let node = self.nodes.take(&encoded_key)?;
+        self.nodes.remove(&encoded_key);
+
+        let path = self
+            .path_where(|k| k.borrow() < key)
+            .into_iter()
+            .take(node.next.len())
+            .map(|prev| prev.cloned())
+            .collect::<Vec<_>>();
+        for (level, (prev, next)) in path.iter().zip(node.next).enumerate() {
+            self.next_keys_mut(prev.as_ref())[level] = next;
+        }
+
+        let head = self.next_keys_mut(None);
+        while let Some(None) = head.last() {
+            head.pop();
+        }
+        let len = self.len.get_mut().expect(
+            "[liquid_lang::SortedMapping::remove] Error: expected `len` field to be \
+             existed in storage",
+        );
+        *len -= 1;
+        Some(node.val)
+    }
+}
+
+impl<K, V> SortedMapping<K, V>
+where
+    K: Codec + Ord,
+    V: Codec,
+{
+    fn entry<'a>(&'a self, key: Option<&'a K>) -> Option<(&'a K, &'a V)> {
+        key.map(|key| (key, &self.node(key).val))
+    }
+
+    /// Returns the entry with the minimum key, if any.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.entry(self.next_of(None))
+    }
+
+    /// Returns the entry with the maximum key, if any.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.entry(self.last_where(|_| true))
+    }
+
+    /// Returns the entry with the minimum key greater than `key`, whether `key`
+    /// is in the map or not.
+    pub fn successor<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let prev = self.last_where(|k| k.borrow() <= key);
+        self.entry(self.next_of(prev))
+    }
+
+    /// Returns the entry with the maximum key less than `key`, whether `key`
+    /// is in the map or not.
+    pub fn predecessor<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entry(self.last_where(|k| k.borrow() < key))
+    }
+
+    /// Returns an iterator over the entries whose keys are in `range`, in
+    /// ascending order of keys.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let front = match range.start_bound() {
+            Bound::Included(start) => {
+                self.next_of(self.last_where(|k| k.borrow() < start))
+            }
+            Bound::Excluded(start) => {
+                self.next_of(self.last_where(|k| k.borrow() <= start))
+            }
+            Bound::Unbounded => self.next_of(None),
+        };
+        let back = match range.end_bound() {
+            Bound::Included(end) => self.last_where(|k| k.borrow() <= end),
+            Bound::Excluded(end) => self.last_where(|k| k.borrow() < end),
+            Bound::Unbounded => self.last_where(|_| true),
+        };
+
+        match (front, back) {
+            (Some(front), Some(back)) if front <= back => Range {
+                sorted_mapping: self,
+                front: Some(front),
+                back: Some(back),
+            },
+            _ => Range {
+                sorted_mapping: self,
+                front: None,
+                back: None,
+            },
+        }
+    }
+
+    /// Returns an iterator over all entries, in ascending order of keys.
+    pub fn iter(&self) -> Range<'_, K, V> {
+        self.range::<K, _>(..)
+    }
+}
+
+impl<'a, K, V, Q> core::ops::Index<&'a Q> for SortedMapping<K, V>
+where
+    K: Borrow<Q> + Codec,
+    V: Codec,
+    Q: Encode,
+{
+    type Output = V;
+
+    fn index(&self, index: &'a Q) -> &Self::Output {
+        self.get(index).expect(
+            "[liquid_lang::SortedMapping::index] Error: expected `index` to be existed",
+        )
+    }
+}
+
+impl<'a, K, V, Q> core::ops::IndexMut<&'a Q> for SortedMapping<K, V>
+where
+    K: Borrow<Q> + Codec,
+    V: Codec,
+    Q: Encode,
+{
+    fn index_mut(&mut self, index: &'a Q) -> &mut Self::Output {
+        self.get_mut(index).expect(
+            "[liquid_lang::SortedMapping::index_mut] Error: expected `index` to be \
+             existed",
+        )
+    }
+}
+
+impl<K, V> Extend<(K, V)> for SortedMapping<K, V>
+where
+    K: Codec + Ord + Clone,
+    V: Codec,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<'a, K, V> Extend<(&'a K, &'a V)> for SortedMapping<K, V>
+where
+    K: Codec + Ord + Copy,
+    V: Codec + Copy,
+{
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().map(|(k, v)| (*k, *v)))
+    }
+}
+
+impl<K: Codec, V: Codec> You_Should_Use_A_Container_To_Wrap_Your_State_Field_In_Storage
+    for SortedMapping<K, V>
+{
+}
@@ -0,0 +1,185 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::SortedMapping;
+use crate::lang_core::storage::traits::{Bind, Flush};
+use core::ops::Bound;
+use scale::Codec;
+
+fn new_empty<K: Codec, V: Codec>() -> SortedMapping<K, V> {
+    let mut map = SortedMapping::<K, V>::bind_with(b"var");
+    map.initialize();
+    map
+}
+
+/// Inserts keys in a shuffled order, with the square of each key as value.
+fn new_squares(keys: &[u32]) -> SortedMapping<u32, u32> {
+    let mut map = new_empty::<u32, u32>();
+    map.extend(keys.iter().map(|key| (*key, key * key)));
+    map
+}
+
+fn keys_of<'a, I: Iterator<Item = (&'a u32, &'a u32)>>(iter: I) -> Vec<u32> {
+    iter.map(|(key, _)| *key).collect()
+}
+
+#[test]
+fn empty() {
+    let map = new_empty::<String, u8>();
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.is_empty(), true);
+    assert_eq!(map.first(), None);
+    assert_eq!(map.last(), None);
+    assert_eq!(map.iter().next(), None);
+}
+
+#[test]
+fn insert_works() {
+    let mut map = new_empty::<String, u8>();
+    let name = "Alice".to_string();
+    assert_eq!(map.insert(name, 0), None);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.is_empty(), false);
+
+    let name = "Alice".to_string();
+    assert_eq!(map.insert(name, 1), Some(0));
+    assert_eq!(map.len(), 1);
+    assert_eq!(map[&"Alice".to_string()], 1);
+}
+
+#[test]
+fn iter_is_sorted() {
+    let keys = (0..100u32).map(|i| i * 37 % 100).collect::<Vec<_>>();
+    let map = new_squares(&keys);
+    assert_eq!(map.len(), 100);
+    assert_eq!(keys_of(map.iter()), (0..100).collect::<Vec<_>>());
+    assert_eq!(
+        keys_of(map.iter().rev()),
+        (0..100).rev().collect::<Vec<_>>()
+    );
+    assert!(map.iter().all(|(key, val)| *val == key * key));
+}
+
+#[test]
+fn remove_works() {
+    let keys = (0..50u32).map(|i| i * 7 % 50).collect::<Vec<_>>();
+    let mut map = new_squares(&keys);
+    for key in (0..50).filter(|key| key % 3 != 0) {
+        assert_eq!(map.remove(&key), Some(key * key));
+    }
+    assert_eq!(map.remove(&1), None);
+    assert_eq!(map.len(), 17);
+    assert_eq!(map.contains_key(&1), false);
+    assert_eq!(
+        keys_of(map.iter()),
+        (0..50).filter(|key| key % 3 == 0).collect::<Vec<_>>()
+    );
+
+    for key in (0..50).filter(|key| key % 3 == 0) {
+        assert_eq!(map.remove(&key), Some(key * key));
+    }
+    assert_eq!(map.is_empty(), true);
+    assert_eq!(map.first(), None);
+}
+
+#[test]
+fn first_and_last() {
+    let map = new_squares(&[5, 1, 9, 3]);
+    assert_eq!(map.first(), Some((&1, &1)));
+    assert_eq!(map.last(), Some((&9, &81)));
+}
+
+#[test]
+fn successor_and_predecessor() {
+    let map = new_squares(&[50, 10, 40, 20, 30]);
+    assert_eq!(map.successor(&20), Some((&30, &900)));
+    assert_eq!(map.successor(&25), Some((&30, &900)));
+    assert_eq!(map.successor(&0), Some((&10, &100)));
+    assert_eq!(map.successor(&50), None);
+    assert_eq!(map.predecessor(&20), Some((&10, &100)));
+    assert_eq!(map.predecessor(&25), Some((&20, &400)));
+    assert_eq!(map.predecessor(&60), Some((&50, &2500)));
+    assert_eq!(map.predecessor(&10), None);
+}
+
+#[test]
+fn range_works() {
+    let map = new_squares(&[50, 10, 40, 20, 30]);
+    assert_eq!(keys_of(map.range(20..40)), [20, 30]);
+    assert_eq!(keys_of(map.range(15..=40)), [20, 30, 40]);
+    assert_eq!(keys_of(map.range(..20)), [10]);
+    assert_eq!(keys_of(map.range(45..)), [50]);
+    assert_eq!(keys_of(map.range(21..29)), Vec::<u32>::new());
+    assert_eq!(keys_of(map.range(60..)), Vec::<u32>::new());
+    assert_eq!(keys_of(map.range(20..=40).rev()), [40, 30, 20]);
+
+    // The first N entries after a key.
+    let mut range = map.range(20..);
+    assert_eq!(range.next(), Some((&20, &400)));
+    assert_eq!(range.next_back(), Some((&50, &2500)));
+    assert_eq!(keys_of(range), [30, 40]);
+}
+
+#[test]
+fn range_with_borrowed_keys() {
+    let mut map = new_empty::<String, u8>();
+    for (i, name) in ["Charlie", "Alice", "Dave", "Bob"].iter().enumerate() {
+        map.insert(name.to_string(), i as u8);
+    }
+    let names = map
+        .range::<str, _>((Bound::Included("B"), Bound::Excluded("D")))
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Bob", "Charlie"]);
+    assert_eq!(map.successor("Bob"), Some((&"Charlie".to_string(), &0)));
+}
+
+#[test]
+fn mutate_with_works() {
+    let mut map = new_squares(&[1, 2]);
+    assert_eq!(map.mutate_with(&1, |val| *val += 10), Some(&11));
+    assert_eq!(map.mutate_with(&3, |val| *val += 10), None);
+    map[&2] = 0;
+    assert_eq!(keys_of(map.iter()), [1, 2]);
+    assert_eq!(map.get(&2), Some(&0));
+}
+
+#[test]
+#[should_panic]
+fn index_failed() {
+    let map = new_empty::<String, u8>();
+    let _ = map[&"Alice".to_string()];
+}
+
+#[test]
+fn flush_works() {
+    let mut map = new_squares(&[3, 1, 2]);
+    map.remove(&2);
+    map.flush();
+
+    let map = SortedMapping::<u32, u32>::bind_with(b"var");
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.iter().collect::<Vec<_>>(), [(&1, &1), (&3, &9)]);
+    assert_eq!(map.get(&2), None);
+}
+
+#[test]
+fn levels_depend_on_bind_key() {
+    use scale::Encode;
+
+    let a = SortedMapping::<u32, u32>::bind_with(b"a");
+    let b = SortedMapping::<u32, u32>::bind_with(b"b");
+    assert!((0..64u32).any(|key| {
+        let encoded_key = key.encode();
+        a.level_of(&encoded_key) != b.level_of(&encoded_key)
+    }));
+}
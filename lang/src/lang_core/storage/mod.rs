@@ -18,7 +18,7 @@ mod traits;
 mod value;
 
 pub use self::{
-    collections::{IterableMapping, Mapping, Vec},
+    collections::{IterableMapping, Mapping, SortedMapping, Vec},
     traits::*,
     value::Value,
 };
@@ -41,6 +41,27 @@ pub mod precompiled {
     pub use super::lang_core::precompiled::*;
 }
 
+/// Prints a message formatted like `format!` for debugging purposes.
+///
+/// On-chain, the message is written via the `printMem` function of the `debug`
+/// host module. In the off-chain environment, the message is printed to stdout
+/// and recorded, which can be read via `env::test::get_debug_messages`. In
+/// release builds, nothing is printed and the call is optimized out.
+///
+/// # Example
+///
+/// ```ignore
+/// liquid_lang::debug!("balance of {:?}: {}", owner, balance);
+/// ```
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if cfg!(debug_assertions) {
+            $crate::env::debug_message(::core::format_args!($($arg)*));
+        }
+    };
+}
+
 use cfg_if::cfg_if;
 
 cfg_if! {
@@ -34,6 +34,9 @@ fn compile_tests() {
     t.pass("tests/contract/common/ui/pass/16-mock-context-getter.rs");
     t.pass("tests/contract/common/ui/pass/17-event.rs");
     t.pass("tests/contract/common/ui/pass/18-array.rs");
+    t.pass("tests/contract/common/ui/pass/19-interface-constructor.rs");
+    t.pass("tests/contract/common/ui/pass/20-interface-at-cns.rs");
+    t.pass("tests/contract/common/ui/pass/21-interface-overloading.rs");
     t.compile_fail("tests/contract/common/ui/fail/01-constructor-returns.rs");
     t.compile_fail("tests/contract/common/ui/fail/02-missing-constructor.rs");
     t.compile_fail("tests/contract/common/ui/fail/03-multiple-constructors.rs");
@@ -89,4 +92,5 @@ fn compile_tests() {
     t.compile_fail("tests/contract/common/ui/fail/47-invalid-mock-context-getter-2.rs");
     t.compile_fail("tests/contract/common/ui/fail/48-invalid-mock-context-getter-3.rs");
     t.compile_fail("tests/contract/common/ui/fail/49-invalid-mock-context-getter-4.rs");
+    t.compile_fail("tests/contract/common/ui/fail/50-reserved-try-fn-name.rs");
 }
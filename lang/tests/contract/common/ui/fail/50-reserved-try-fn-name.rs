@@ -0,0 +1,11 @@
+use liquid_lang as liquid;
+
+#[liquid::interface(name = auto)]
+mod foo {
+    extern "liquid" {
+        fn bar(&self);
+        fn try_bar(&self) -> bool;
+    }
+}
+
+fn main() {}
@@ -14,6 +14,7 @@ mod noop {
         pub b: storage::Vec<bool>,
         pub c: storage::Mapping<String, bool>,
         pub d: storage::IterableMapping<String, bool>,
+        pub e: storage::SortedMapping<String, bool>,
     }
 
     #[liquid(methods)]
@@ -39,6 +40,11 @@ mod noop {
             #[allow(deprecated)]
             self.d(String::from(""))
         }
+
+        pub fn noop_4(&self) -> bool {
+            #[allow(deprecated)]
+            self.e(String::from(""))
+        }
     }
 }
 
@@ -0,0 +1,41 @@
+#![feature(unboxed_closures, fn_traits)]
+
+use liquid::storage;
+use liquid_lang as liquid;
+
+#[liquid::interface(name = auto)]
+mod counter {
+    extern "solidity" {
+        fn new(&mut self, init: u32, owner: address);
+        fn get(&self) -> u32;
+    }
+}
+
+#[liquid::contract]
+mod factory {
+    use super::{counter::*, *};
+
+    #[liquid(storage)]
+    struct Factory {
+        counters: storage::Vec<Counter>,
+    }
+
+    #[liquid(methods)]
+    impl Factory {
+        pub fn new(&mut self) {
+            self.counters.initialize();
+        }
+
+        pub fn make(&mut self, code: bytes, init: u32) -> bool {
+            match Counter::deploy(&code, init, self.env().get_caller()) {
+                Ok(counter) => {
+                    self.counters.push(counter);
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+    }
+}
+
+fn main() {}
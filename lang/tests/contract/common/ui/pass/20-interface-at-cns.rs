@@ -0,0 +1,35 @@
+#![feature(unboxed_closures, fn_traits)]
+
+use liquid::storage;
+use liquid_lang as liquid;
+
+#[liquid::interface(name = auto)]
+mod counter {
+    extern "solidity" {
+        fn get(&self) -> u32;
+    }
+}
+
+#[liquid::contract]
+mod user {
+    use super::{counter::*, *};
+
+    #[liquid(storage)]
+    struct User {
+        counter: storage::Value<Counter>,
+    }
+
+    #[liquid(methods)]
+    impl User {
+        pub fn new(&mut self) {
+            let counter = Counter::at_cns("Counter", "1.0").unwrap();
+            self.counter.initialize(counter);
+        }
+
+        pub fn is_registered(&self, version: String) -> bool {
+            Counter::at_cns("Counter", &version).is_some()
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+#![feature(unboxed_closures, fn_traits)]
+
+use liquid::storage;
+use liquid_lang as liquid;
+
+#[liquid::interface(name = auto)]
+mod registry {
+    extern "solidity" {
+        fn set(&mut self, key: String, value: address) -> bool;
+        fn set(&mut self, key: String, value: u32) -> bool;
+    }
+}
+
+#[liquid::contract]
+mod user {
+    use super::{registry::*, *};
+
+    #[liquid(storage)]
+    struct User {
+        registry: storage::Value<Registry>,
+    }
+
+    #[liquid(methods)]
+    impl User {
+        pub fn new(&mut self, registry: address) {
+            self.registry.initialize(Registry::at(registry));
+        }
+
+        pub fn set(&mut self, key: String, value: u32) -> String {
+            let _ = (self.registry.set)(key.clone(), address::default());
+            match (self.registry.try_set)(key, value) {
+                Ok(_) => String::new(),
+                Err(error) => error.reason.unwrap_or_default(),
+            }
+        }
+    }
+}
+
+fn main() {}
@@ -60,6 +60,11 @@ pub trait Topics {
     fn topics(&self) -> Vec<types::Hash>;
 }
 
+/// The signature of an event, which is always the first topic of the event.
+pub trait EventSignature {
+    fn signature() -> types::Hash;
+}
+
 cfg_if! {
     if #[cfg(feature = "contract")] {
         #[allow(non_camel_case_types)]